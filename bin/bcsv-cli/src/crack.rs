@@ -0,0 +1,114 @@
+use bcsv::{Collision, CrackOptions, HashDictionary, NameHash, Table};
+use clap::Args;
+use color_eyre::eyre::{bail, eyre};
+use color_eyre::Result;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct CrackArgs {
+    /// BCSV files whose unknown column hashes are cracked
+    files: Vec<PathBuf>,
+
+    /// Additional hashes to crack, like 0x1A2B3C4D
+    #[arg(long = "hash", value_name = "hash", value_parser = parse_hash)]
+    hashes: Vec<u32>,
+
+    /// Word lists that are combined into names, their names also count as known
+    #[arg(short, long, value_name = "file")]
+    words: Vec<PathBuf>,
+
+    /// Prefixes that are put in front of names, like l_ or Obj_
+    #[arg(short, long, value_name = "prefix")]
+    prefix: Vec<String>,
+
+    /// Strings that are put between two words, defaults to nothing
+    #[arg(short, long, value_name = "separator")]
+    separator: Vec<String>,
+
+    /// The maximum number of words in a name
+    #[arg(short = 'n', long, value_name = "count", default_value_t = 2)]
+    max_words: usize,
+
+    /// Tries the numbers up to this one as suffixes
+    #[arg(short = 'x', long, value_name = "number")]
+    numbers: Option<u32>,
+
+    /// Tries every name up to this length after each prefix
+    #[arg(short, long, value_name = "length", default_value_t = 0)]
+    brute_force: usize,
+
+    /// The characters used for brute forcing
+    #[arg(long, value_name = "characters")]
+    charset: Option<String>,
+
+    /// The number of threads, defaults to one per core
+    #[arg(short = 'j', long, value_name = "threads", default_value_t = 0)]
+    threads: usize,
+}
+
+fn parse_hash(value: &str) -> Result<u32> {
+    let hex = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    u32::from_str_radix(hex, 16).map_err(|_| eyre!("`{value}` isn't a hexadecimal hash"))
+}
+
+pub fn crack(args: CrackArgs) -> Result<()> {
+    let mut dictionary = HashDictionary::new();
+    for path in &args.words {
+        dictionary.merge(HashDictionary::open(path)?);
+    }
+
+    let mut targets = args.hashes.iter().copied().collect::<HashSet<_>>();
+    for path in &args.files {
+        let (table, _) = Table::open_auto(path)?;
+        for definition in table.definitions() {
+            match dictionary.get(definition.name) {
+                Some(name) => eprintln!("{:#010X} is known as {name}", definition.name),
+                None => {
+                    targets.insert(definition.name);
+                }
+            }
+        }
+    }
+
+    if targets.is_empty() {
+        bail!("There are no unknown hashes to crack");
+    }
+
+    // The dictionary names are already sorted.
+    let mut options = CrackOptions {
+        words: dictionary.names().map(String::from).collect(),
+        prefixes: args.prefix,
+        max_words: args.max_words,
+        max_number: args.numbers,
+        brute_force_length: args.brute_force,
+        threads: args.threads,
+        ..CrackOptions::default()
+    };
+    if !args.separator.is_empty() {
+        options.separators = args.separator;
+    }
+    if let Some(charset) = args.charset {
+        options.charset = charset.into_bytes();
+    }
+
+    eprintln!(
+        "Cracking {} hashes with {} words",
+        targets.len(),
+        options.words.len()
+    );
+    bcsv::crack(&targets, &options, print_collision);
+
+    Ok(())
+}
+
+fn print_collision(collision: Collision) {
+    let function = match collision.function {
+        NameHash::Jgadget => "jgadget",
+        NameHash::Old => "old",
+    };
+    println!("{:#010X} {function} {}", collision.hash, collision.name);
+}
@@ -1,16 +1,61 @@
-use super::AppSettings;
 use atty::Stream;
-use bcsv::{DataType, DataValue, Table};
+use bcsv::byteorder::LittleEndian;
+use bcsv::{DataType, DataValue, HashDictionary, Table};
+use clap::Args;
 use color_eyre::Result;
 use comfy_table::{modifiers, presets, Attribute, Cell, CellAlignment};
 use csv::{StringRecord, Writer};
-use std::collections::HashMap;
+use std::io::{self, Cursor, Read};
+use std::path::PathBuf;
 
-pub fn decode(options: AppSettings) -> Result<()> {
-    let crack_map = options.create_crack_map()?;
-    let bcsv = options.read_input_bcsv()?;
+#[derive(Args)]
+pub struct DecodeArgs {
+    /// Input file, defaults to stdin
+    input: Option<PathBuf>,
 
-    let header = create_header_record(&bcsv, &crack_map);
+    /// Output file, defaults to stdout
+    output: Option<PathBuf>,
+
+    /// Word lists used to turn column hashes back into names
+    #[arg(long, short, value_name = "file")]
+    words: Vec<PathBuf>,
+}
+
+impl DecodeArgs {
+    fn read_input_bcsv(&self, little_endian: bool) -> Result<Table> {
+        match &self.input {
+            Some(x) => Ok(if little_endian {
+                Table::open::<LittleEndian>(x)?
+            } else {
+                Table::open_auto(x)?.0
+            }),
+            None => {
+                let mut stdin = io::stdin();
+                let mut buffer = Vec::new();
+                stdin.read_to_end(&mut buffer)?;
+                Ok(if little_endian {
+                    Table::read::<LittleEndian>(Cursor::new(buffer))?
+                } else {
+                    Table::read_auto(Cursor::new(buffer))?.0
+                })
+            }
+        }
+    }
+
+    fn create_dictionary(&self) -> Result<HashDictionary> {
+        let mut result = HashDictionary::new();
+        for path in &self.words {
+            result.merge(HashDictionary::open(path)?);
+        }
+        Ok(result)
+    }
+}
+
+pub fn decode(options: DecodeArgs, little_endian: bool) -> Result<()> {
+    let dictionary = options.create_dictionary()?;
+    let bcsv = options.read_input_bcsv(little_endian)?;
+
+    let header = create_header_record(&bcsv, &dictionary);
 
     if let Some(path) = options.output {
         let mut writer = Writer::from_path(path)?;
@@ -77,10 +122,10 @@ fn write_row_terminal(table: &mut comfy_table::Table, row: &[DataValue]) {
     }));
 }
 
-fn create_header_record(bcsv: &Table, crack_map: &HashMap<u32, String>) -> StringRecord {
+fn create_header_record(bcsv: &Table, dictionary: &HashDictionary) -> StringRecord {
     let mut header = Vec::with_capacity(bcsv.column_count());
-    for definition in bcsv.definitions() {
-        header.push(match crack_map.get(&definition.name) {
+    for (column, definition) in bcsv.definitions().iter().enumerate() {
+        header.push(match bcsv.column_name(column, dictionary) {
             Some(x) => format!("{}({})", x, definition.ty),
             None => format!("{:#X}({})", definition.name, definition.ty),
         });
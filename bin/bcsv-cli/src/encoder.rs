@@ -1,9 +1,45 @@
-use super::AppSettings;
 use bcsv::byteorder::{BigEndian, LittleEndian};
 use bcsv::{DataType, DataValue, Table};
+use clap::Args;
 use color_eyre::Result;
+use csv::{Reader, StringRecord};
+use std::path::PathBuf;
 
-pub fn encode(options: AppSettings) -> Result<()> {
+#[derive(Args)]
+pub struct EncodeArgs {
+    /// Input file, defaults to stdin
+    input: Option<PathBuf>,
+
+    /// Output file, defaults to stdout
+    output: Option<PathBuf>,
+}
+
+impl EncodeArgs {
+    fn read_input_csv(&self) -> Result<(StringRecord, Vec<StringRecord>)> {
+        match &self.input {
+            Some(x) => {
+                let mut reader = Reader::from_path(x)?;
+                let header = reader.headers()?.clone();
+                let mut body = Vec::new();
+                for row in reader.into_records() {
+                    body.push(row?);
+                }
+                Ok((header, body))
+            }
+            None => {
+                let mut reader = Reader::from_reader(std::io::stdin());
+                let header = reader.headers()?.clone();
+                let mut body = Vec::new();
+                for row in reader.into_records() {
+                    body.push(row?);
+                }
+                Ok((header, body))
+            }
+        }
+    }
+}
+
+pub fn encode(options: EncodeArgs, little_endian: bool) -> Result<()> {
     let (headers, rows) = options.read_input_csv()?;
 
     let mut bcsv = Table::new();
@@ -21,7 +57,7 @@ pub fn encode(options: AppSettings) -> Result<()> {
         let mut mapped = bcsv
             .definitions()
             .iter()
-            .zip(row.into_iter())
+            .zip(row)
             .enumerate()
             .map(|(j, (definition, data))| match definition.ty {
                 DataType::Int32 => match data.parse::<i32>() {
@@ -31,10 +67,7 @@ pub fn encode(options: AppSettings) -> Result<()> {
                         DataValue::Int32(0)
                     }
                 },
-                DataType::InlineString => {
-                    eprintln!("Inline string converted to offset string in row {i} column {j}");
-                    DataValue::OffsetString(data.into())
-                }
+                DataType::InlineString => DataValue::InlineString(data.into()),
                 DataType::Float => match data.parse::<f32>() {
                     Ok(x) => DataValue::Float(x),
                     Err(e) => {
@@ -73,7 +106,7 @@ pub fn encode(options: AppSettings) -> Result<()> {
 
     match options.output {
         Some(x) => {
-            if options.little_endian {
+            if little_endian {
                 bcsv.save::<LittleEndian>(x)?;
             } else {
                 bcsv.save::<BigEndian>(x)?;
@@ -82,7 +115,7 @@ pub fn encode(options: AppSettings) -> Result<()> {
         None => {
             let writer = std::io::stdout().lock();
 
-            if options.little_endian {
+            if little_endian {
                 bcsv.write::<LittleEndian>(writer)?;
             } else {
                 bcsv.write::<BigEndian>(writer)?;
@@ -104,7 +137,7 @@ fn find_data_type(header: &str) -> (u32, DataType) {
                     "Short" => DataType::Int16,
                     "Char" => DataType::Int8,
                     "Null" => DataType::Null,
-                    _ => DataType::OffsetString,
+                    x => x.parse().unwrap_or(DataType::OffsetString),
                 },
                 &header[..idx],
             ),
@@ -1,104 +1,36 @@
+mod crack;
 mod decoder;
 mod encoder;
 
-use bcsv::byteorder::{BigEndian, LittleEndian};
-use bcsv::Table;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use color_eyre::Result;
-use csv::{Reader, StringRecord};
-use std::collections::HashMap;
-use std::fs;
-use std::io::{self, BufRead, BufReader, Cursor, Read};
-use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "BCSV Tool")]
 #[command(about = "Convert between Nintendo BCSV and normal CSV files", long_about = None)]
 #[command(author, version)]
+#[command(disable_help_subcommand = true)]
 pub struct AppSettings {
-    /// Input file
-    input: Option<PathBuf>,
-
-    /// Output file
-    output: Option<PathBuf>,
-
-    /// Tires to crack field name hashes based on the given file
-    #[arg(long, short)]
-    crack: Option<PathBuf>,
-
-    /// Turns the tool into decode mode
-    #[arg(long, short)]
-    decode: bool,
-
-    /// Will use little endain encoding instead of big endian
-    #[arg(long, short)]
+    /// Will use little endain encoding instead of big endian, when decoding the byte order is detected otherwise
+    #[arg(long, short, global = true)]
     little_endian: bool,
-}
 
-impl AppSettings {
-    pub fn read_input_bcsv(&self) -> Result<Table> {
-        match &self.input {
-            Some(x) => Ok(if self.little_endian {
-                Table::open::<LittleEndian>(x)?
-            } else {
-                Table::open::<BigEndian>(x)?
-            }),
-            None => {
-                let mut stdin = io::stdin();
-                let mut buffer = Vec::new();
-                stdin.read_to_end(&mut buffer)?;
-                Ok(if self.little_endian {
-                    Table::read::<LittleEndian>(Cursor::new(buffer))?
-                } else {
-                    Table::read::<BigEndian>(Cursor::new(buffer))?
-                })
-            }
-        }
-    }
-
-    pub fn read_input_csv(&self) -> Result<(StringRecord, Vec<StringRecord>)> {
-        match &self.input {
-            Some(x) => {
-                let mut reader = Reader::from_path(x)?;
-                let header = reader.headers()?.clone();
-                let mut body = Vec::new();
-                for row in reader.into_records() {
-                    body.push(row?);
-                }
-                Ok((header, body))
-            }
-            None => {
-                let mut reader = Reader::from_reader(std::io::stdin());
-                let header = reader.headers()?.clone();
-                let mut body = Vec::new();
-                for row in reader.into_records() {
-                    body.push(row?);
-                }
-                Ok((header, body))
-            }
-        }
-    }
-
-    pub fn create_crack_map(&self) -> Result<HashMap<u32, String>> {
-        let path = match &self.crack {
-            Some(path) => path,
-            None => return Ok(HashMap::new()),
-        };
-
-        let file = BufReader::new(fs::File::open(path)?);
-        let mut result = HashMap::new();
-        for line in file.lines() {
-            let line = line?;
+    #[command(subcommand)]
+    command: Command,
+}
 
-            if line.starts_with('#') {
-                continue;
-            }
+#[derive(Subcommand)]
+enum Command {
+    /// Converts a BCSV file into a CSV file
+    #[command(visible_alias = "d")]
+    Decode(decoder::DecodeArgs),
 
-            result.insert(bcsv::jgadget_hash(line.as_bytes()), line);
-        }
+    /// Converts a CSV file into a BCSV file
+    #[command(visible_alias = "e")]
+    Encode(encoder::EncodeArgs),
 
-        Ok(result)
-    }
+    /// Searches for names that match unknown column hashes
+    Crack(crack::CrackArgs),
 }
 
 fn main() -> Result<()> {
@@ -106,10 +38,10 @@ fn main() -> Result<()> {
 
     let options = AppSettings::parse();
 
-    if options.decode {
-        decoder::decode(options)?;
-    } else {
-        encoder::encode(options)?;
+    match options.command {
+        Command::Decode(args) => decoder::decode(args, options.little_endian)?,
+        Command::Encode(args) => encoder::encode(args, options.little_endian)?,
+        Command::Crack(args) => crack::crack(args)?,
     };
 
     Ok(())
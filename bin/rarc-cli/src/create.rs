@@ -0,0 +1,152 @@
+use crate::utils::{self, BaseArgs, BaseOptions, Compression, FileInput};
+use clap::Args;
+use color_eyre::eyre::bail;
+use color_eyre::Result;
+use rarc::compression::CompressionLevel;
+use rarc::{Archive, Endian};
+use std::ffi::{OsStr, OsString};
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct CreateArgs {
+    #[command(flatten)]
+    base: BaseArgs,
+
+    /// Sets the compression level between 1 and 10 with 1 being faster but bigger and 10 bing smaller but slower
+    #[arg(short, long, value_name = "level", default_value_t = 10)]
+    level: u8,
+
+    /// Sets how the archive is compressed, defaults to yaz0 or the format from the manifest
+    #[arg(short, long, value_enum)]
+    compression: Option<Compression>,
+
+    /// Exclude files that match the given patterns
+    #[arg(short, long, value_name = "pattern")]
+    exclude: Vec<String>,
+
+    /// Name of the root directory, defaults to the name of the archive
+    #[arg(short, long, value_name = "name")]
+    root: Option<String>,
+
+    /// Packs a single directory, restoring the attributes and order from its manifest
+    #[arg(short, long)]
+    manifest: bool,
+
+    /// Addes the given files or directories to the archive that is being created
+    ///
+    /// Addes the given files or directories to the archive that is being created.
+    /// Existing archives can also be merged into this one, by prefixing there path with an @ symbol.
+    #[arg(value_name = "file|directory|@archive")]
+    input: Vec<OsString>,
+}
+
+enum CreateFileInput {
+    File(PathBuf),
+    Archive(PathBuf),
+}
+
+impl CreateFileInput {
+    fn new(value: &OsStr) -> Self {
+        match archive_path(value) {
+            Some(archive) => CreateFileInput::Archive(archive),
+            None => CreateFileInput::File(value.into()),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn archive_path(input: &OsStr) -> Option<PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+    let buffer = input.as_bytes();
+    buffer
+        .strip_prefix(b"@")
+        .map(|path| OsStr::from_bytes(path).into())
+}
+
+#[cfg(not(unix))]
+fn archive_path(input: &OsStr) -> Option<PathBuf> {
+    input.to_str()?.strip_prefix('@').map(PathBuf::from)
+}
+
+pub fn create(args: CreateArgs, endian: Option<Endian>) -> Result<()> {
+    let base = BaseOptions::from(args.base);
+    let exclude = utils::create_globset(&args.exclude)?;
+
+    if args.manifest {
+        let directory = match &args.input[..] {
+            [directory] => Path::new(directory),
+            _ => bail!("Packing with a manifest expects exactly one directory"),
+        };
+
+        base.vprintln(format_args!("Packing directory: {}", directory.display()));
+        let mut output = Archive::from_directory(directory)?;
+        if let Some(root) = &args.root {
+            output.set_root_name(root)?;
+        }
+        if let Some(compression) = args.compression {
+            output.set_compression(compression.into());
+        }
+        return write_output(&base, output, endian, args.level);
+    }
+
+    let root = match (&args.root, &base.file) {
+        (Some(root), _) => root.clone(),
+        (None, FileInput::Path(path)) => path
+            .file_stem()
+            .and_then(|x| x.to_str())
+            .unwrap_or("root")
+            .into(),
+        (None, FileInput::Stdin) => "root".into(),
+    };
+
+    let mut output = Archive::new(&root)?;
+    output.set_compression(args.compression.unwrap_or(Compression::Yaz0).into());
+
+    for input in args.input.iter().map(|x| CreateFileInput::new(x)) {
+        match input {
+            CreateFileInput::File(path) => {
+                let target = match utils::archive_path(&path) {
+                    Some(x) if !x.is_empty() => x,
+                    _ => {
+                        eprintln!("Cannot insert file with broken path: {}", path.display());
+                        continue;
+                    }
+                };
+
+                utils::add_path(&base, &mut output, &path, &target, &exclude)?;
+            }
+            CreateFileInput::Archive(path) => {
+                utils::merge_archive(&base, &mut output, &path, &exclude, endian)?;
+            }
+        }
+    }
+
+    write_output(&base, output, endian, args.level)
+}
+
+fn write_output<F: Read + Seek>(
+    base: &BaseOptions,
+    mut archive: Archive<F>,
+    endian: Option<Endian>,
+    level: u8,
+) -> Result<()> {
+    archive.set_compression_level(CompressionLevel::Level(level.clamp(1, 10)));
+    let endian = endian.unwrap_or(Endian::Big);
+
+    match &base.file {
+        FileInput::Path(path) => {
+            let mut writer = BufWriter::new(File::create(path)?);
+            utils::write_archive(&mut archive, endian, &mut writer)?;
+            writer.flush()?;
+        }
+        FileInput::Stdin => {
+            let mut stdout = io::stdout().lock();
+            utils::write_archive(&mut archive, endian, &mut stdout)?;
+            stdout.flush()?;
+        }
+    }
+
+    Ok(())
+}
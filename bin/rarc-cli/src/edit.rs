@@ -0,0 +1,235 @@
+use crate::utils::{self, BaseOptions, Compression, EditArgs, FileInput, Load};
+use clap::Args;
+use color_eyre::eyre::{bail, eyre};
+use color_eyre::Result;
+use rarc::{Archive, Endian, EntryKind};
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct AddArgs {
+    #[command(flatten)]
+    edit: EditArgs,
+
+    /// Directory inside of the archive to add the files to, using only their names
+    #[arg(short, long, value_name = "directory")]
+    directory: Option<String>,
+
+    /// Exclude files that match the given patterns
+    #[arg(short, long, value_name = "pattern")]
+    exclude: Vec<String>,
+
+    /// Files or directories to add, replacing existing files with the same path
+    #[arg(value_name = "file|directory", required = true)]
+    input: Vec<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct DeleteArgs {
+    #[command(flatten)]
+    edit: EditArgs,
+
+    /// Deletes the entries that match the given patterns, directories are deleted with all of their contents
+    #[arg(required = true)]
+    patterns: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct RenameArgs {
+    #[command(flatten)]
+    edit: EditArgs,
+
+    /// The current path of the entry
+    from: String,
+
+    /// The new path of the entry
+    to: String,
+}
+
+#[derive(Args)]
+pub struct AttrArgs {
+    #[command(flatten)]
+    edit: EditArgs,
+
+    /// Sets how the game loads the files
+    #[arg(long, value_enum)]
+    load: Option<Load>,
+
+    /// Sets how the files are compressed inside of the archive
+    #[arg(short, long, value_enum)]
+    compression: Option<Compression>,
+
+    /// Sets the ID of a file, which is ignored if the archive keeps its IDs synced.
+    /// The patterns have to match exactly one file, since IDs have to be unique
+    #[arg(long)]
+    id: Option<u16>,
+
+    /// Sets the 4 character tag of directories
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Shows or changes the attributes of the entries that match the given patterns
+    #[arg(required = true)]
+    patterns: Vec<String>,
+}
+
+fn open(base: &BaseOptions, endian: Option<Endian>) -> Result<(Archive<Cursor<Vec<u8>>>, Endian)> {
+    base.vprintln(format_args!("Reading archive from: {}", base.file));
+    base.file.read_archive(endian)
+}
+
+/// Writes the archive back in the byte order it was read in.
+fn save(base: &BaseOptions, mut archive: Archive<Cursor<Vec<u8>>>, endian: Endian) -> Result<()> {
+    base.vprintln(format_args!("Writing archive to: {}", base.file));
+    match &base.file {
+        FileInput::Path(path) => {
+            // The whole archive is in memory, so it can be written back to the same file.
+            let mut buffer = Cursor::new(Vec::new());
+            utils::write_archive(&mut archive, endian, &mut buffer)?;
+            fs::write(path, buffer.into_inner())?;
+        }
+        FileInput::Stdin => unreachable!("archives are always edited in place"),
+    }
+    Ok(())
+}
+
+fn matching_entries(
+    archive: &Archive<Cursor<Vec<u8>>>,
+    patterns: &[String],
+) -> Result<Vec<(String, EntryKind)>> {
+    let patterns = utils::create_globset(patterns)?;
+    let entries = archive
+        .iter()
+        .filter(|(path, _)| *path != "/" && patterns.is_match(utils::entry_name(path)))
+        .map(|(path, kind)| (path.to_string(), kind))
+        .collect::<Vec<_>>();
+
+    if entries.is_empty() {
+        bail!("No entries match the given patterns");
+    }
+    Ok(entries)
+}
+
+pub fn add(args: AddArgs, endian: Option<Endian>) -> Result<()> {
+    let base = BaseOptions::from(&args.edit);
+    let exclude = utils::create_globset(&args.exclude)?;
+    let (mut archive, endian) = open(&base, endian)?;
+
+    for input in &args.input {
+        // Files that are added to a directory keep only their name, like with `tar -C`.
+        let target = match &args.directory {
+            Some(directory) => input
+                .file_name()
+                .and_then(|x| x.to_str())
+                .map(|name| format!("/{}/{name}", directory.trim_matches('/'))),
+            None => utils::archive_path(input).filter(|x| !x.is_empty()),
+        };
+        let target = match target {
+            Some(x) => x.replace("//", "/"),
+            None => bail!("Cannot insert file with broken path: {}", input.display()),
+        };
+
+        utils::add_path(&base, &mut archive, input, &target, &exclude)?;
+    }
+
+    save(&base, archive, endian)
+}
+
+pub fn delete(args: DeleteArgs, endian: Option<Endian>) -> Result<()> {
+    let base = BaseOptions::from(&args.edit);
+    let (mut archive, endian) = open(&base, endian)?;
+
+    for (path, _) in matching_entries(&archive, &args.patterns)? {
+        if archive.remove(&path)? {
+            base.vprintln(format_args!("Deleting: {}", utils::entry_name(&path)));
+        }
+    }
+
+    save(&base, archive, endian)
+}
+
+pub fn rename(args: RenameArgs, endian: Option<Endian>) -> Result<()> {
+    let base = BaseOptions::from(&args.edit);
+    let (mut archive, endian) = open(&base, endian)?;
+
+    base.vprintln(format_args!("Renaming {} to {}", args.from, args.to));
+    archive.mv(&args.from, &args.to)?;
+
+    save(&base, archive, endian)
+}
+
+pub fn attr(args: AttrArgs, endian: Option<Endian>) -> Result<()> {
+    let base = BaseOptions::from(&args.edit);
+    let (mut archive, endian) = open(&base, endian)?;
+    let entries = matching_entries(&archive, &args.patterns)?;
+
+    let tag = match &args.tag {
+        Some(tag) => Some(
+            <[u8; 4]>::try_from(tag.as_bytes())
+                .map_err(|_| eyre!("The tag `{tag}` isn't 4 characters long"))?,
+        ),
+        None => None,
+    };
+
+    let modify =
+        args.load.is_some() || args.compression.is_some() || args.id.is_some() || tag.is_some();
+    if !modify {
+        for (path, kind) in entries {
+            let name = utils::entry_name(&path);
+            match kind {
+                EntryKind::Directory => {
+                    let tag = archive.directory_tag(&path).unwrap_or_default();
+                    println!("{name}: directory {:?}", String::from_utf8_lossy(&tag));
+                }
+                EntryKind::File => {
+                    let file = archive.open_file(&path).unwrap();
+                    let attributes = file.attributes();
+                    println!(
+                        "{name}: id {} {:#04x} {}",
+                        file.id(),
+                        attributes.bits(),
+                        utils::describe_attributes(attributes)
+                    );
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    // Giving several files the same ID would break the archive.
+    if args.id.is_some() && !matches!(entries.as_slice(), [(_, EntryKind::File)]) {
+        bail!(
+            "An ID can only be set for a single file, but the patterns match {} entries",
+            entries.len()
+        );
+    }
+
+    for (path, kind) in entries {
+        base.vprintln(format_args!("Updating: {}", utils::entry_name(&path)));
+        match kind {
+            EntryKind::Directory => {
+                if tag.is_some() {
+                    archive.set_directory_tag(&path, tag)?;
+                }
+            }
+            EntryKind::File => {
+                let mut file = archive.open_file(&path).unwrap();
+                let mut attributes = file.attributes();
+                if let Some(load) = args.load {
+                    attributes.set_load_class(load.into());
+                }
+                if let Some(compression) = args.compression {
+                    attributes.set_compression(compression.into());
+                }
+                file.set_attributes(attributes);
+
+                if let Some(id) = args.id {
+                    file.set_id(id);
+                }
+            }
+        }
+    }
+
+    save(&base, archive, endian)
+}
@@ -0,0 +1,78 @@
+use crate::utils::{self, BaseArgs, BaseOptions};
+use clap::Args;
+use color_eyre::eyre::bail;
+use color_eyre::Result;
+use rarc::{Endian, EntryKind};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ExtractArgs {
+    #[command(flatten)]
+    base: BaseArgs,
+
+    /// A directory to output to
+    #[arg(short, long, value_name = "directory")]
+    output: Option<PathBuf>,
+
+    /// Writes a manifest next to the extracted files, so they can be packed into the same archive again
+    #[arg(short, long, conflicts_with = "patterns")]
+    manifest: bool,
+
+    /// Only extracts entries that macht the given patterns
+    patterns: Vec<String>,
+}
+
+pub fn extract(args: ExtractArgs, endian: Option<Endian>) -> Result<()> {
+    let base = BaseOptions::from(args.base);
+    let patterns = utils::create_globset(&args.patterns)?;
+    let output = match args.output {
+        Some(x) => x,
+        None => std::env::current_dir()?,
+    };
+
+    base.vprintln(format_args!("Reading archive from: {}", base.file));
+    let (mut archive, _) = base.file.read_archive(endian)?;
+
+    base.vprintln(format_args!("Writing to: {}", output.display()));
+    if args.manifest {
+        archive.extract_to(&output)?;
+        return Ok(());
+    }
+
+    fs::create_dir_all(&output)?;
+
+    let entries = archive
+        .iter()
+        .map(|(path, kind)| (path.to_string(), kind))
+        .filter(|(path, _)| path != "/")
+        .collect::<Vec<_>>();
+
+    for (path, kind) in entries {
+        let name = utils::entry_name(&path);
+        if !patterns.is_empty() && !patterns.is_match(name) {
+            continue;
+        }
+
+        let target = match utils::output_path(&output, name) {
+            Some(x) => x,
+            None => bail!("Refusing to extract entry outside of the output directory: {name}"),
+        };
+        match kind {
+            EntryKind::File => {
+                base.vprintln(format_args!("Extracting file: {}", target.display()));
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let data = archive.open_file(&path).unwrap().read_to_vec()?;
+                fs::write(target, data)?;
+            }
+            EntryKind::Directory => {
+                base.vprintln(format_args!("Creating directory: {}", target.display()));
+                fs::create_dir_all(target)?;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::utils::{self, BaseArgs, BaseOptions};
+use clap::Args;
+use color_eyre::Result;
+use humansize::{format_size, WINDOWS};
+use rarc::Endian;
+use rarc::EntryKind;
+
+#[derive(Args)]
+pub struct ListArgs {
+    #[command(flatten)]
+    base: BaseArgs,
+
+    /// Displays file sizes in a more human-friendly way
+    #[arg(short = 'u', long)]
+    humansize: bool,
+
+    /// Only show entries that mach the given patterns
+    patterns: Vec<String>,
+}
+
+pub fn list(args: ListArgs, endian: Option<Endian>) -> Result<()> {
+    let base = BaseOptions::from(args.base);
+    let patterns = utils::create_globset(&args.patterns)?;
+    let (mut archive, _) = base.file.read_archive(endian)?;
+
+    let entries = archive
+        .iter()
+        .map(|(path, kind)| (path.to_string(), kind))
+        .filter(|(path, _)| path != "/")
+        .filter(|(path, _)| patterns.is_empty() || patterns.is_match(utils::entry_name(path)))
+        .collect::<Vec<_>>();
+
+    if !base.verbose {
+        for (path, _) in &entries {
+            println!("{}", utils::entry_name(path));
+        }
+        return Ok(());
+    }
+
+    let mut collected = Vec::with_capacity(entries.len());
+    for (path, kind) in &entries {
+        let (ty, len) = match kind {
+            EntryKind::Directory => ('d', 0),
+            EntryKind::File => ('-', archive.open_file(path).unwrap().size()),
+        };
+
+        let size = if args.humansize {
+            let mut size = format_size(len, WINDOWS.space_after_value(false));
+
+            let mut i = size.chars();
+            i.next_back();
+            if let Some(x) = i.next_back() {
+                if x.is_numeric() {
+                    size.push(' ');
+                }
+            }
+
+            size
+        } else {
+            len.to_string()
+        };
+
+        collected.push((ty, size, utils::entry_name(path)));
+    }
+
+    let max_len = collected
+        .iter()
+        .map(|(_, size, _)| size.len())
+        .max()
+        .unwrap_or_default();
+    for (ty, size, path) in collected {
+        println!("{} {:>3$} {}", ty, size, path, max_len);
+    }
+
+    Ok(())
+}
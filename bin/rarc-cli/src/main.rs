@@ -0,0 +1,85 @@
+mod create;
+mod edit;
+mod extract;
+mod list;
+mod utils;
+
+use clap::{Parser, Subcommand};
+use color_eyre::Result;
+use rarc::Endian;
+
+#[derive(Parser)]
+#[command(name = "RARC Tool")]
+#[command(about = "Manipulate Nintendo RARC archives", long_about = None)]
+#[command(author, version)]
+#[command(disable_help_subcommand = true)]
+pub struct AppSettings {
+    /// Will use little endian encoding, instead of detecting it from existing archives or defaulting to big endian
+    #[arg(long, global = true, conflicts_with = "big_endian")]
+    little_endian: bool,
+
+    /// Will use big endian encoding, instead of detecting it from existing archives
+    #[arg(long, global = true)]
+    big_endian: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Creates a RARC archive
+    #[command(visible_alias = "c")]
+    Create(create::CreateArgs),
+
+    /// Lists all entries in a RARC archive
+    #[command(visible_aliases = ["l", "t"])]
+    List(list::ListArgs),
+
+    /// Extracts a RARC archive
+    #[command(visible_aliases = ["e", "x"])]
+    Extract(extract::ExtractArgs),
+
+    /// Adds files or directories to an existing archive
+    #[command(visible_alias = "a")]
+    Add(edit::AddArgs),
+
+    /// Deletes entries from an existing archive
+    #[command(visible_aliases = ["d", "rm"])]
+    Delete(edit::DeleteArgs),
+
+    /// Renames or moves an entry of an existing archive
+    #[command(visible_alias = "mv")]
+    Rename(edit::RenameArgs),
+
+    /// Shows or changes the attributes of entries in an existing archive
+    Attr(edit::AttrArgs),
+}
+
+fn run(command: Command, endian: Option<Endian>) -> Result<()> {
+    match command {
+        Command::Create(args) => create::create(args, endian),
+        Command::List(args) => list::list(args, endian),
+        Command::Extract(args) => extract::extract(args, endian),
+        Command::Add(args) => edit::add(args, endian),
+        Command::Delete(args) => edit::delete(args, endian),
+        Command::Rename(args) => edit::rename(args, endian),
+        Command::Attr(args) => edit::attr(args, endian),
+    }
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+
+    let options = AppSettings::parse();
+
+    let endian = if options.little_endian {
+        Some(Endian::Little)
+    } else if options.big_endian {
+        Some(Endian::Big)
+    } else {
+        None
+    };
+
+    run(options.command, endian)
+}
@@ -0,0 +1,304 @@
+use clap::{Args, ValueEnum};
+use color_eyre::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rarc::byteorder::{BigEndian, LittleEndian};
+use rarc::compression::Format;
+use rarc::{Archive, Endian, EntryKind, FileAttributes, LoadClass};
+use std::fmt::{self, Display, Write};
+use std::fs;
+use std::io::{self, Cursor, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Args)]
+pub struct BaseArgs {
+    /// Location of the archive
+    #[arg(short, long, value_name = "filename")]
+    file: Option<PathBuf>,
+
+    /// Make output verbose
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[derive(Args)]
+pub struct EditArgs {
+    /// Location of the archive
+    #[arg(short, long, value_name = "filename")]
+    pub file: PathBuf,
+
+    /// Make output verbose
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Compression {
+    None,
+    Yaz0,
+    Yay0,
+}
+
+impl From<Compression> for Option<Format> {
+    fn from(value: Compression) -> Self {
+        match value {
+            Compression::None => None,
+            Compression::Yaz0 => Some(Format::Yaz0),
+            Compression::Yay0 => Some(Format::Yay0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Load {
+    None,
+    Mram,
+    Aram,
+    Dvd,
+}
+
+impl From<Load> for Option<LoadClass> {
+    fn from(value: Load) -> Self {
+        match value {
+            Load::None => None,
+            Load::Mram => Some(LoadClass::Mram),
+            Load::Aram => Some(LoadClass::Aram),
+            Load::Dvd => Some(LoadClass::Dvd),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq)]
+pub enum FileInput {
+    Stdin,
+    Path(PathBuf),
+}
+
+impl FileInput {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        match path {
+            Some(x) if x.as_os_str() != "-" => FileInput::Path(x),
+            _ => FileInput::Stdin,
+        }
+    }
+
+    pub fn read_archive(
+        &self,
+        endian: Option<Endian>,
+    ) -> Result<(Archive<Cursor<Vec<u8>>>, Endian)> {
+        let buffer = match self {
+            FileInput::Path(path) => fs::read(path)?,
+            FileInput::Stdin => {
+                let mut buffer = Vec::new();
+                std::io::stdin().read_to_end(&mut buffer)?;
+                buffer
+            }
+        };
+
+        read_archive(buffer, endian)
+    }
+}
+
+impl Display for FileInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileInput::Stdin => f.write_char('-'),
+            FileInput::Path(path) => path.display().fmt(f),
+        }
+    }
+}
+
+pub struct BaseOptions {
+    pub file: FileInput,
+    pub verbose: bool,
+}
+
+impl BaseOptions {
+    #[inline]
+    pub fn vprintln(&self, args: fmt::Arguments) {
+        if self.verbose {
+            if self.file == FileInput::Stdin {
+                eprintln!("{}", args);
+            } else {
+                println!("{}", args);
+            }
+        }
+    }
+}
+
+impl From<BaseArgs> for BaseOptions {
+    fn from(value: BaseArgs) -> Self {
+        BaseOptions {
+            file: FileInput::new(value.file),
+            verbose: value.verbose,
+        }
+    }
+}
+
+impl From<&EditArgs> for BaseOptions {
+    fn from(value: &EditArgs) -> Self {
+        BaseOptions {
+            file: FileInput::Path(value.file.clone()),
+            verbose: value.verbose,
+        }
+    }
+}
+
+/// Reads an archive in the given byte order, or detects the order from its header.
+pub fn read_archive(
+    buffer: Vec<u8>,
+    endian: Option<Endian>,
+) -> Result<(Archive<Cursor<Vec<u8>>>, Endian)> {
+    Ok(match endian {
+        Some(Endian::Big) => (Archive::from_vec::<BigEndian>(buffer)?, Endian::Big),
+        Some(Endian::Little) => (Archive::from_vec::<LittleEndian>(buffer)?, Endian::Little),
+        None => Archive::from_vec_auto(buffer)?,
+    })
+}
+
+pub fn write_archive<F: Read + Seek>(
+    archive: &mut Archive<F>,
+    endian: Endian,
+    writer: impl io::Write,
+) -> Result<()> {
+    match endian {
+        Endian::Big => archive.write::<BigEndian>(writer)?,
+        Endian::Little => archive.write::<LittleEndian>(writer)?,
+    }
+    Ok(())
+}
+
+pub fn create_globset(patterns: &[String]) -> Result<GlobSet> {
+    let mut result = GlobSetBuilder::new();
+    for pattern in patterns {
+        result.add(Glob::new(pattern)?);
+    }
+    Ok(result.build()?)
+}
+
+/// Gets the path of an entry like it is shown to the user, without the leading slash.
+pub fn entry_name(path: &str) -> &str {
+    path.trim_start_matches('/')
+}
+
+/// Turns the name of an entry into a path inside of the output directory.
+/// Returns [`None`] if the name could point outside of it, like with `..` components or absolute paths.
+pub fn output_path(output: &Path, name: &str) -> Option<PathBuf> {
+    let name = Path::new(name);
+    name.components()
+        .all(|x| matches!(x, Component::Normal(_)))
+        .then(|| output.join(name))
+}
+
+/// Turns a path on disk into a path inside of an archive, dropping any root or `.` components.
+pub fn archive_path(path: &Path) -> Option<String> {
+    let mut result = String::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(x) => {
+                result.push('/');
+                result.push_str(x.to_str()?);
+            }
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            Component::ParentDir => return None,
+        }
+    }
+    Some(result)
+}
+
+/// Adds a file or a whole directory from disk to the archive at the given path.
+pub fn add_path<F: Read + Seek>(
+    base: &BaseOptions,
+    archive: &mut Archive<F>,
+    source: &Path,
+    target: &str,
+    exclude: &GlobSet,
+) -> Result<()> {
+    if exclude.is_match(entry_name(target)) {
+        return Ok(());
+    }
+
+    if source.is_dir() {
+        base.vprintln(format_args!("Creating directory: {}", entry_name(target)));
+        archive.create_directory(target)?;
+
+        let mut entries = fs::read_dir(source)?
+            .map(|entry| entry.map(|x| x.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+
+        for entry in entries {
+            let name = match entry.file_name().and_then(|x| x.to_str()) {
+                Some(x) => x,
+                None => {
+                    eprintln!("Cannot insert file with broken path: {}", entry.display());
+                    continue;
+                }
+            };
+            add_path(base, archive, &entry, &format!("{target}/{name}"), exclude)?;
+        }
+    } else {
+        base.vprintln(format_args!("Adding file: {}", entry_name(target)));
+        archive.create_file(target, source)?;
+    }
+
+    Ok(())
+}
+
+/// Copies every entry of another archive into the given one, keeping their attributes and tags.
+pub fn merge_archive<F: Read + Seek>(
+    base: &BaseOptions,
+    archive: &mut Archive<F>,
+    source: &Path,
+    exclude: &GlobSet,
+    endian: Option<Endian>,
+) -> Result<()> {
+    base.vprintln(format_args!("Merging archive: {}", source.display()));
+    let (mut source, _) = read_archive(fs::read(source)?, endian)?;
+
+    let entries = source
+        .iter()
+        .filter(|(path, _)| *path != "/")
+        .map(|(path, kind)| (path.to_string(), kind))
+        .collect::<Vec<_>>();
+
+    for (path, kind) in entries {
+        if exclude.is_match(entry_name(&path)) {
+            continue;
+        }
+
+        match kind {
+            EntryKind::Directory => {
+                base.vprintln(format_args!("Creating directory: {}", entry_name(&path)));
+                archive.create_directory(&path)?;
+                if let Some(tag) = source.custom_directory_tag(&path) {
+                    archive.set_directory_tag(&path, Some(tag))?;
+                }
+            }
+            EntryKind::File => {
+                base.vprintln(format_args!("Adding file: {}", entry_name(&path)));
+                let mut file = source.open_file(&path).unwrap();
+                let attributes = file.attributes();
+                archive.create_file(&path, file.read_to_vec()?)?;
+                archive.open_file(&path).unwrap().set_attributes(attributes);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Describes the attributes of a file in a short form, like `mram yaz0`.
+pub fn describe_attributes(attributes: FileAttributes) -> String {
+    let load = match attributes.load_class() {
+        Some(LoadClass::Mram) => "mram",
+        Some(LoadClass::Aram) => "aram",
+        Some(LoadClass::Dvd) => "dvd",
+        None => "none",
+    };
+
+    match attributes.compression() {
+        Some(Format::Yaz0) => format!("{load} yaz0"),
+        Some(Format::Yay0) => format!("{load} yay0"),
+        None => load.into(),
+    }
+}
@@ -0,0 +1,47 @@
+use rarc::byteorder::BigEndian;
+use rarc::Archive;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+fn attr(archive: &Path, args: &[&str]) -> ExitStatus {
+    Command::new(env!("CARGO_BIN_EXE_rarc-cli"))
+        .arg("attr")
+        .arg("-f")
+        .arg(archive)
+        .args(args)
+        .output()
+        .unwrap()
+        .status
+}
+
+#[test]
+fn ids_are_only_set_for_a_single_file() {
+    let dir = std::env::temp_dir().join(format!("rarc-cli-attr-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_file("/jmp/List", b"list".to_vec()).unwrap();
+    archive.create_file("/jmp/Base", b"base".to_vec()).unwrap();
+    archive.set_keep_synced(false);
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+    let path = dir.join("stage.arc");
+    fs::write(&path, &data).unwrap();
+
+    let several = attr(&path, &["--id", "7", "jmp/*"]);
+    let unchanged = fs::read(&path).unwrap();
+    let directory = attr(&path, &["--id", "7", "jmp"]);
+    let single = attr(&path, &["--id", "7", "jmp/List"]);
+    let changed = fs::read(&path).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(!several.success());
+    assert_eq!(unchanged, data);
+    assert!(!directory.success());
+    assert!(single.success());
+    let mut archive = Archive::from_vec::<BigEndian>(changed).unwrap();
+    assert_eq!(archive.open_file("/jmp/List").unwrap().id(), 7);
+    assert_ne!(archive.open_file("/jmp/Base").unwrap().id(), 7);
+}
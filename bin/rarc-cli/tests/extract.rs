@@ -0,0 +1,89 @@
+use rarc::byteorder::{BigEndian, ByteOrder};
+use rarc::{name_hash, Archive};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A directory for a single test, which is removed again when it is dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("rarc-cli-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TempDir(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Writes an archive with a single file, whose name is patched in afterwards so it can be anything.
+fn archive(name: &str) -> Vec<u8> {
+    let placeholder = "x".repeat(name.len());
+    let mut archive = Archive::new("stage").unwrap();
+    archive
+        .create_file(&format!("/{placeholder}"), b"payload".to_vec())
+        .unwrap();
+
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+
+    let string_offset = BigEndian::read_u32(&data[0x34..]) as usize + 0x20;
+    let start = string_offset
+        + data[string_offset..]
+            .windows(name.len())
+            .position(|x| x == placeholder.as_bytes())
+            .unwrap();
+    data[start..(start + name.len())].copy_from_slice(name.as_bytes());
+
+    let file_offset = BigEndian::read_u32(&data[0x2C..]) as usize + 0x20;
+    let file_nodes = BigEndian::read_u32(&data[0x28..]) as usize;
+    let stale = name_hash(&placeholder);
+    for node in data[file_offset..(file_offset + file_nodes * 0x14)].chunks_mut(0x14) {
+        if BigEndian::read_u16(&node[2..]) == stale {
+            BigEndian::write_u16(&mut node[2..], name_hash(name));
+        }
+    }
+    data
+}
+
+fn extract(dir: &Path, data: &[u8]) -> bool {
+    let input = dir.join("input.arc");
+    fs::write(&input, data).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_rarc-cli"))
+        .arg("extract")
+        .arg("-f")
+        .arg(&input)
+        .arg("-o")
+        .arg(dir.join("out/inner"))
+        .output()
+        .unwrap()
+        .status
+        .success()
+}
+
+#[test]
+fn extracts_into_the_output_directory() {
+    let dir = TempDir::new("extract");
+    assert!(extract(&dir.0, &archive("pwned_")));
+    assert_eq!(
+        fs::read(dir.0.join("out/inner/pwned_")).unwrap(),
+        b"payload"
+    );
+}
+
+#[test]
+fn refuses_to_escape_the_output_directory() {
+    let dir = TempDir::new("traversal");
+    for name in ["../../pwned_", "..\\..\\pwned"] {
+        assert!(!extract(&dir.0, &archive(name)));
+    }
+    assert!(!dir.0.join("pwned_").exists());
+    assert!(!dir.0.join("out/pwned_").exists());
+    assert!(!dir.0.join("out/inner/pwned_").exists());
+}
@@ -0,0 +1,37 @@
+use rarc::byteorder::BigEndian;
+use rarc::Archive;
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn merging_keeps_only_custom_tags() {
+    let dir = std::env::temp_dir().join(format!("rarc-cli-merge-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_file("/jmp/List", b"list".to_vec()).unwrap();
+    archive.create_directory("/cam").unwrap();
+    archive.set_directory_tag("/cam", Some(*b"ABCD")).unwrap();
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+    fs::write(dir.join("input.arc"), data).unwrap();
+
+    let output = dir.join("output.arc");
+    let status = Command::new(env!("CARGO_BIN_EXE_rarc-cli"))
+        .arg("create")
+        .arg("-c")
+        .arg("none")
+        .arg("-f")
+        .arg(&output)
+        .arg(format!("@{}", dir.join("input.arc").display()))
+        .status()
+        .unwrap();
+    let merged = fs::read(&output);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(status.success());
+    let archive = Archive::from_vec::<BigEndian>(merged.unwrap()).unwrap();
+    assert_eq!(archive.custom_directory_tag("/jmp"), None);
+    assert_eq!(archive.custom_directory_tag("/cam"), Some(*b"ABCD"));
+}
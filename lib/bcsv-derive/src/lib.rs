@@ -0,0 +1,359 @@
+//! # BCSV Row Derive
+//!
+//! Implements `bcsv::BcsvRow` for structs, so their column layout is checked at compile time.
+//! This crate is used through the `derive` feature of `bcsv`.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitInt, LitStr, Result, Type,
+};
+
+/// Derives `bcsv::BcsvRow` for a struct with named fields.
+///
+/// Every field becomes one column, which can be configured with a `#[bcsv(...)]` attribute:
+/// - `name = "..."` is the name that gets hashed, it defaults to the field name.
+/// - `hash = 0x...` sets the name hash directly, for columns with unknown names.
+/// - `ty = Int32` sets the column type, which is inferred for primitive types, `bool` and `String`.
+/// - `offset = ...` places the column in a row, it defaults to right after the previous columns.
+/// - `bitmask = ...` and `shift = ...` select the bits of an integer column.
+///
+/// Columns can only share bytes if they are integers of the same type at the same offset with disjoint bitmasks.
+/// Putting `#[bcsv(old_hash)]` on the struct hashes names with `old_hash` instead of `jgadget_hash`.
+#[proc_macro_derive(BcsvRow, attributes(bcsv))]
+pub fn derive_bcsv_row(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive(input) {
+        Ok(x) => x.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Int32,
+    InlineString,
+    Float,
+    UInt32,
+    Int16,
+    Int8,
+    OffsetString,
+    Null,
+}
+
+impl ColumnType {
+    fn parse(ident: &Ident) -> Result<Self> {
+        Ok(match ident.to_string().as_str() {
+            "Int32" => ColumnType::Int32,
+            "InlineString" => ColumnType::InlineString,
+            "Float" => ColumnType::Float,
+            "UInt32" => ColumnType::UInt32,
+            "Int16" => ColumnType::Int16,
+            "Int8" => ColumnType::Int8,
+            "OffsetString" => ColumnType::OffsetString,
+            "Null" => ColumnType::Null,
+            _ => return Err(Error::new(ident.span(), "unknown BCSV data type")),
+        })
+    }
+
+    fn infer(ty: &Type) -> Option<Self> {
+        let Type::Path(path) = ty else { return None };
+        let ident = path.path.get_ident()?;
+        Some(match ident.to_string().as_str() {
+            "i32" | "bool" => ColumnType::Int32,
+            "u32" => ColumnType::UInt32,
+            "f32" => ColumnType::Float,
+            "i16" | "u16" => ColumnType::Int16,
+            "i8" | "u8" => ColumnType::Int8,
+            "String" => ColumnType::OffsetString,
+            _ => return None,
+        })
+    }
+
+    fn size(self) -> u32 {
+        match self {
+            ColumnType::Int32 => 4,
+            ColumnType::InlineString => 32,
+            ColumnType::Float => 4,
+            ColumnType::UInt32 => 4,
+            ColumnType::Int16 => 2,
+            ColumnType::Int8 => 1,
+            ColumnType::OffsetString => 4,
+            ColumnType::Null => 0,
+        }
+    }
+
+    fn is_integer(self) -> bool {
+        matches!(
+            self,
+            ColumnType::Int32 | ColumnType::UInt32 | ColumnType::Int16 | ColumnType::Int8
+        )
+    }
+
+    fn ident(self) -> Ident {
+        let name = match self {
+            ColumnType::Int32 => "Int32",
+            ColumnType::InlineString => "InlineString",
+            ColumnType::Float => "Float",
+            ColumnType::UInt32 => "UInt32",
+            ColumnType::Int16 => "Int16",
+            ColumnType::Int8 => "Int8",
+            ColumnType::OffsetString => "OffsetString",
+            ColumnType::Null => "Null",
+        };
+        Ident::new(name, Span::call_site())
+    }
+}
+
+enum ColumnName {
+    Name(String),
+    Hash(u32),
+}
+
+struct Column {
+    field: Ident,
+    span: Span,
+    name: ColumnName,
+    ty: ColumnType,
+    offset: u32,
+    bitmask: u32,
+    shift: u8,
+}
+
+fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let mut old_hash = false;
+    for attr in input.attrs.iter().filter(|x| x.path().is_ident("bcsv")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("old_hash") {
+                old_hash = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown bcsv attribute, expected `old_hash`"))
+            }
+        })?;
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "BcsvRow can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "BcsvRow can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut columns: Vec<Column> = Vec::with_capacity(fields.len());
+    for field in fields {
+        let column = parse_column(field, &columns)?;
+        check_overlap(&column, &columns)?;
+        columns.push(column);
+    }
+
+    let hash_function = if old_hash {
+        quote!(::bcsv::NameHash::Old)
+    } else {
+        quote!(::bcsv::NameHash::Jgadget)
+    };
+
+    let definitions = columns.iter().map(|column| {
+        let name = match &column.name {
+            ColumnName::Name(name) => quote!(#hash_function.hash(#name)),
+            ColumnName::Hash(hash) => quote!(#hash),
+        };
+        let ty = column.ty.ident();
+        let offset = column.offset as u16;
+        let bitmask = column.bitmask;
+        let shift = column.shift;
+        quote_spanned! {column.span=>
+            ::bcsv::ColumnDefinition {
+                name: #name,
+                bitmask: #bitmask,
+                offset: #offset,
+                shift: #shift,
+                ty: ::bcsv::DataType::#ty,
+            }
+        }
+    });
+
+    let from_row = columns.iter().enumerate().map(|(index, column)| {
+        let field = &column.field;
+        quote_spanned! {column.span=>
+            #field: ::bcsv::ColumnValue::from_value(&row[#index])?
+        }
+    });
+
+    let to_row = columns.iter().map(|column| {
+        let field = &column.field;
+        let ty = column.ty.ident();
+        quote_spanned! {column.span=>
+            ::bcsv::ColumnValue::to_value(&self.#field, ::bcsv::DataType::#ty)?
+        }
+    });
+
+    let count = columns.len();
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bcsv::BcsvRow for #ident #ty_generics #where_clause {
+            fn definitions() -> ::std::vec::Vec<::bcsv::ColumnDefinition> {
+                ::std::vec![#(#definitions),*]
+            }
+
+            fn from_row(row: &[::bcsv::DataValue]) -> ::std::result::Result<Self, ::bcsv::BcsvError> {
+                if row.len() != #count {
+                    return ::std::result::Result::Err(::bcsv::BcsvError::InvaildRowLength {
+                        expected: #count,
+                        actual: row.len(),
+                    });
+                }
+                ::std::result::Result::Ok(Self {
+                    #(#from_row),*
+                })
+            }
+
+            fn to_row(&self) -> ::std::result::Result<::std::vec::Vec<::bcsv::DataValue>, ::bcsv::BcsvError> {
+                ::std::result::Result::Ok(::std::vec![#(#to_row),*])
+            }
+        }
+    })
+}
+
+fn parse_column(field: &syn::Field, previous: &[Column]) -> Result<Column> {
+    let ident = field.ident.clone().expect("named fields have an ident");
+    let mut name = None;
+    let mut ty = None;
+    let mut offset = None;
+    let mut bitmask = None;
+    let mut shift = None;
+
+    for attr in field.attrs.iter().filter(|x| x.path().is_ident("bcsv")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = Some(ColumnName::Name(value.value()));
+            } else if meta.path.is_ident("hash") {
+                let value: LitInt = meta.value()?.parse()?;
+                name = Some(ColumnName::Hash(value.base10_parse()?));
+            } else if meta.path.is_ident("ty") {
+                let value: Ident = meta.value()?.parse()?;
+                ty = Some(ColumnType::parse(&value)?);
+            } else if meta.path.is_ident("offset") {
+                let value: LitInt = meta.value()?.parse()?;
+                offset = Some(value.base10_parse::<u16>()? as u32);
+            } else if meta.path.is_ident("bitmask") {
+                let value: LitInt = meta.value()?.parse()?;
+                bitmask = Some(value.base10_parse::<u32>()?);
+            } else if meta.path.is_ident("shift") {
+                let value: LitInt = meta.value()?.parse()?;
+                let value = value.base10_parse::<u8>()?;
+                if value >= 32 {
+                    return Err(meta.error("the shift has to be less than 32"));
+                }
+                shift = Some(value);
+            } else {
+                return Err(meta.error(
+                    "unknown bcsv attribute, expected `name`, `hash`, `ty`, `offset`, `bitmask` or `shift`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    let span = field.span();
+    let ty = match ty.or_else(|| ColumnType::infer(&field.ty)) {
+        Some(x) => x,
+        None => {
+            return Err(Error::new(
+                field.ty.span(),
+                "the column type can't be inferred, add `#[bcsv(ty = ...)]`",
+            ))
+        }
+    };
+
+    if (bitmask.is_some() || shift.is_some()) && !ty.is_integer() {
+        return Err(Error::new(
+            span,
+            "only integer columns can have a bitmask or shift",
+        ));
+    }
+    let bits = ty.size() * 8;
+    if shift.is_some_and(|x| x as u32 >= bits) {
+        return Err(Error::new(
+            span,
+            format!("the shift has to be less than the {bits} bits of the column type"),
+        ));
+    }
+
+    let offset = offset.unwrap_or_else(|| {
+        previous
+            .iter()
+            .map(|x| x.offset + x.ty.size())
+            .max()
+            .unwrap_or_default()
+    });
+    if offset + ty.size() > u16::MAX as u32 {
+        return Err(Error::new(span, "the column doesn't fit into a row"));
+    }
+
+    let name = name.unwrap_or_else(|| ColumnName::Name(ident.to_string()));
+    for column in previous {
+        let duplicate = match (&column.name, &name) {
+            (ColumnName::Name(a), ColumnName::Name(b)) => a == b,
+            (ColumnName::Hash(a), ColumnName::Hash(b)) => a == b,
+            _ => false,
+        };
+        if duplicate {
+            return Err(Error::new(span, "another column already has this name"));
+        }
+    }
+
+    Ok(Column {
+        field: ident,
+        span,
+        name,
+        ty,
+        offset,
+        bitmask: bitmask.unwrap_or(u32::MAX),
+        shift: shift.unwrap_or(0),
+    })
+}
+
+/// Makes sure a column only shares storage with packed integers of the same type.
+fn check_overlap(column: &Column, previous: &[Column]) -> Result<()> {
+    let start = column.offset;
+    let end = start + column.ty.size();
+
+    for other in previous {
+        let other_start = other.offset;
+        let other_end = other_start + other.ty.size();
+        if start >= other_end || other_start >= end {
+            continue;
+        }
+
+        let packed = column.ty.is_integer()
+            && column.ty == other.ty
+            && start == other_start
+            && column.bitmask & other.bitmask == 0;
+        if !packed {
+            return Err(Error::new(
+                column.span,
+                format!(
+                    "the column overlaps `{}`, columns can only share bytes with disjoint bitmasks",
+                    other.field
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use crate::NameHash;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// A name that hashes to one of the targets of [`crack`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Collision {
+    /// The hash that was matched.
+    pub hash: u32,
+    /// The hash function that produced the hash.
+    pub function: NameHash,
+    /// The name that was found.
+    pub name: String,
+}
+
+/// Options that control which names [`crack`] tries.
+///
+/// Every candidate is made of an optional prefix, one or more words joined by a separator and an optional numeric suffix.
+/// With a brute force length, every string of up to that many characters from the charset is tried after each prefix as well.
+#[derive(Debug, Clone)]
+pub struct CrackOptions {
+    /// The words that get combined.
+    pub words: Vec<String>,
+    /// Prefixes like `l_` or `Obj_`, names without a prefix are always tried.
+    pub prefixes: Vec<String>,
+    /// The strings put between two words.
+    pub separators: Vec<String>,
+    /// The maximum number of words in a name.
+    pub max_words: usize,
+    /// Tries the numbers up to this one as suffixes, like `Arg0` or `Switch12`.
+    pub max_number: Option<u32>,
+    /// The maximum length of brute forced names, which is turned off with 0.
+    pub brute_force_length: usize,
+    /// The characters used for brute forcing.
+    pub charset: Vec<u8>,
+    /// The number of threads, 0 uses one thread per core.
+    pub threads: usize,
+}
+
+impl Default for CrackOptions {
+    fn default() -> Self {
+        CrackOptions {
+            words: Vec::new(),
+            prefixes: Vec::new(),
+            separators: vec![String::new()],
+            max_words: 2,
+            max_number: None,
+            brute_force_length: 0,
+            charset: (b'a'..=b'z')
+                .chain(b'A'..=b'Z')
+                .chain(b'0'..=b'9')
+                .chain([b'_'])
+                .collect(),
+            threads: 0,
+        }
+    }
+}
+
+/// Tries to find names for the given hashes, using both [`old_hash`](`crate::old_hash`) and [`jgadget_hash`](`crate::jgadget_hash`).
+/// Every collision is passed to `report` as soon as it is found, from whichever thread found it.
+/// Short hashes collide a lot, so one hash can be reported with many names.
+pub fn crack(targets: &HashSet<u32>, options: &CrackOptions, report: impl Fn(Collision) + Sync) {
+    if targets.is_empty() {
+        return;
+    }
+
+    let threads = match options.threads {
+        0 => thread::available_parallelism().map_or(1, |x| x.get()),
+        x => x,
+    };
+
+    let mut prefixes = vec![String::new()];
+    prefixes.extend(options.prefixes.iter().filter(|x| !x.is_empty()).cloned());
+
+    // Work is split into jobs by prefix and first word or character, which threads take one by one.
+    let mut jobs = Vec::new();
+    for prefix in 0..prefixes.len() {
+        for word in 0..options.words.len() {
+            jobs.push(Job::Words { prefix, word });
+        }
+        if options.brute_force_length > 0 {
+            for character in 0..options.charset.len() {
+                jobs.push(Job::BruteForce { prefix, character });
+            }
+        }
+    }
+
+    let suffixes = match options.max_number {
+        Some(max) => (0..=max).map(|x| x.to_string()).collect(),
+        None => Vec::new(),
+    };
+
+    let cracker = Cracker {
+        targets,
+        options,
+        suffixes,
+        report: &report,
+    };
+    let next = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for _ in 0..threads.min(jobs.len()) {
+            scope.spawn(|| {
+                while let Some(job) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    match *job {
+                        Job::Words { prefix, word } => {
+                            let mut name = prefixes[prefix].clone();
+                            let state = State::default().push(name.as_bytes());
+                            cracker.words(&mut name, state, word, 1);
+                        }
+                        Job::BruteForce { prefix, character } => {
+                            let mut name = prefixes[prefix].clone().into_bytes();
+                            let state = State::default().push(&name);
+                            cracker.brute_force(&mut name, state, character, 1);
+                        }
+                    }
+                }
+            });
+        }
+    });
+}
+
+/// Collects every collision of [`crack`] into a sorted list.
+pub fn crack_all(targets: &HashSet<u32>, options: &CrackOptions) -> Vec<Collision> {
+    let result = std::sync::Mutex::new(Vec::new());
+    crack(targets, options, |x| result.lock().unwrap().push(x));
+
+    let mut result = result.into_inner().unwrap();
+    result.sort();
+    result.dedup();
+    result
+}
+
+#[derive(Clone, Copy)]
+enum Job {
+    Words { prefix: usize, word: usize },
+    BruteForce { prefix: usize, character: usize },
+}
+
+/// The state of both hash functions, so names can be hashed while they are built.
+#[derive(Clone, Copy, Default)]
+struct State {
+    jgadget: u32,
+    old: u32,
+}
+
+impl State {
+    fn push(mut self, bytes: &[u8]) -> Self {
+        for c in bytes {
+            self.jgadget = self.jgadget.wrapping_mul(0x1F).wrapping_add(*c as u32);
+            self.old = ((self.old << 8).wrapping_add(*c as u32)) % 33554393;
+        }
+        self
+    }
+}
+
+struct Cracker<'a, F> {
+    targets: &'a HashSet<u32>,
+    options: &'a CrackOptions,
+    suffixes: Vec<String>,
+    report: &'a F,
+}
+
+impl<'a, F: Fn(Collision)> Cracker<'a, F> {
+    fn check(&self, name: &[u8], state: State) {
+        if self.targets.contains(&state.jgadget) {
+            (self.report)(Collision {
+                hash: state.jgadget,
+                function: NameHash::Jgadget,
+                name: String::from_utf8_lossy(name).into_owned(),
+            });
+        }
+        if self.targets.contains(&state.old) {
+            (self.report)(Collision {
+                hash: state.old,
+                function: NameHash::Old,
+                name: String::from_utf8_lossy(name).into_owned(),
+            });
+        }
+    }
+
+    /// Appends a word to the name and tries it with every suffix, before adding more words.
+    fn words(&self, name: &mut String, state: State, word: usize, count: usize) {
+        let length = name.len();
+        name.push_str(&self.options.words[word]);
+        let state = state.push(self.options.words[word].as_bytes());
+
+        self.check(name.as_bytes(), state);
+        for suffix in &self.suffixes {
+            let length = name.len();
+            name.push_str(suffix);
+            self.check(name.as_bytes(), state.push(suffix.as_bytes()));
+            name.truncate(length);
+        }
+
+        if count < self.options.max_words {
+            for separator in &self.options.separators {
+                let length = name.len();
+                name.push_str(separator);
+                let state = state.push(separator.as_bytes());
+                for next in 0..self.options.words.len() {
+                    self.words(name, state, next, count + 1);
+                }
+                name.truncate(length);
+            }
+        }
+
+        name.truncate(length);
+    }
+
+    fn brute_force(&self, name: &mut Vec<u8>, state: State, character: usize, length: usize) {
+        let c = self.options.charset[character];
+        name.push(c);
+        let state = state.push(&[c]);
+
+        self.check(name, state);
+        if length < self.options.brute_force_length {
+            for next in 0..self.options.charset.len() {
+                self.brute_force(name, state, next, length + 1);
+            }
+        }
+
+        name.pop();
+    }
+}
@@ -1,4 +1,5 @@
 use crate::BcsvError;
+use std::cmp::Ordering;
 use std::fmt::{self, Display};
 use std::str::FromStr;
 
@@ -10,8 +11,8 @@ pub enum DataType {
     /// A 32-bit integer [`i32`].
     Int32 = 0,
 
-    /// A 32 byte inline string.
-    /// This type should be seen as depricated and use of [`OffsetString`](`DataType::OffsetString`) should be prefered.
+    /// A 32 byte inline string, padded with NUL bytes.
+    /// This type is mostly found in older tables, use of [`OffsetString`](`DataType::OffsetString`) should be prefered for new ones.
     InlineString = 1,
 
     /// An IEEE 32-bit single precision floating point value [`f32`].
@@ -117,7 +118,7 @@ impl FromStr for DataType {
 }
 
 /// A enum representing on the the values of [`DataType`].
-#[derive(Clone)]
+#[derive(Clone, Default, PartialEq)]
 pub enum DataValue {
     Int32(i32),
     InlineString(String),
@@ -126,6 +127,7 @@ pub enum DataValue {
     Int16(i16),
     Int8(i8),
     OffsetString(String),
+    #[default]
     Null,
 }
 
@@ -143,6 +145,21 @@ impl DataValue {
             DataValue::Null => DataType::Null,
         }
     }
+
+    /// Compares two values, ordering floats with [`f32::total_cmp`].
+    /// Values of different types are ordered by their type.
+    pub fn total_cmp(&self, other: &DataValue) -> Ordering {
+        match (self, other) {
+            (DataValue::Int32(a), DataValue::Int32(b)) => a.cmp(b),
+            (DataValue::InlineString(a), DataValue::InlineString(b)) => a.cmp(b),
+            (DataValue::Float(a), DataValue::Float(b)) => a.total_cmp(b),
+            (DataValue::UInt32(a), DataValue::UInt32(b)) => a.cmp(b),
+            (DataValue::Int16(a), DataValue::Int16(b)) => a.cmp(b),
+            (DataValue::Int8(a), DataValue::Int8(b)) => a.cmp(b),
+            (DataValue::OffsetString(a), DataValue::OffsetString(b)) => a.cmp(b),
+            (a, b) => (a.ty() as u8).cmp(&(b.ty() as u8)),
+        }
+    }
 }
 
 impl Display for DataValue {
@@ -160,12 +177,6 @@ impl Display for DataValue {
     }
 }
 
-impl Default for DataValue {
-    fn default() -> Self {
-        DataValue::Null
-    }
-}
-
 impl From<i32> for DataValue {
     fn from(value: i32) -> Self {
         DataValue::Int32(value)
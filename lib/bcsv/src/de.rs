@@ -0,0 +1,204 @@
+use crate::{BcsvError, ColumnDefinition, DataValue, NameHash, Result, Table};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use std::fmt::Display;
+
+impl de::Error for BcsvError {
+    fn custom<T: Display>(msg: T) -> Self {
+        BcsvError::Serde(msg.to_string())
+    }
+}
+
+impl Table {
+    /// Deserializes every row of this table into the given type.
+    /// Struct fields are matched to columns by the hash of their name, see [`NameHash::hash`].
+    /// Fields without a matching column are treated as missing, so they need a `#[serde(default)]`.
+    /// Tuples and sequences get the values of a row in column order.
+    pub fn deserialize_rows<D: DeserializeOwned>(&self, hash: NameHash) -> Result<Vec<D>> {
+        self.iter()
+            .map(|row| {
+                D::deserialize(RowDeserializer {
+                    row,
+                    fields: self.definitions(),
+                    hash,
+                })
+            })
+            .collect()
+    }
+}
+
+struct RowDeserializer<'a> {
+    row: &'a [DataValue],
+    fields: &'a [ColumnDefinition],
+    hash: NameHash,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for RowDeserializer<'a> {
+    type Error = BcsvError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_seq(RowValues {
+            values: self.row.iter(),
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let columns = fields
+            .iter()
+            .filter_map(|field| {
+                let hash = self.hash.hash(field);
+                let column = self.fields.iter().position(|x| x.name == hash)?;
+                Some((*field, &self.row[column]))
+            })
+            .collect::<Vec<_>>();
+
+        visitor.visit_map(RowFields {
+            columns: columns.into_iter(),
+            value: None,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct RowValues<'a> {
+    values: std::slice::Iter<'a, DataValue>,
+}
+
+impl<'de, 'a> SeqAccess<'de> for RowValues<'a> {
+    type Error = BcsvError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.values.next() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct RowFields<'a> {
+    columns: std::vec::IntoIter<(&'static str, &'a DataValue)>,
+    value: Option<&'a DataValue>,
+}
+
+impl<'de, 'a> MapAccess<'de> for RowFields<'a> {
+    type Error = BcsvError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.columns.next() {
+            Some((field, value)) => {
+                self.value = Some(value);
+                seed.deserialize(field.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)),
+            None => Err(de::Error::custom("a value was requested before its key")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.columns.len())
+    }
+}
+
+struct ValueDeserializer<'a>(&'a DataValue);
+
+impl<'de, 'a> de::Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = BcsvError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            DataValue::Int32(x) => visitor.visit_i32(*x),
+            DataValue::InlineString(x) => visitor.visit_str(x),
+            DataValue::Float(x) => visitor.visit_f32(*x),
+            DataValue::UInt32(x) => visitor.visit_u32(*x),
+            DataValue::Int16(x) => visitor.visit_i16(*x),
+            DataValue::Int8(x) => visitor.visit_i8(*x),
+            DataValue::OffsetString(x) => visitor.visit_str(x),
+            DataValue::Null => visitor.visit_unit(),
+        }
+    }
+
+    /// Flags are stored as integers, so any integer that isn't zero is true.
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            DataValue::Int32(x) => visitor.visit_bool(*x != 0),
+            DataValue::UInt32(x) => visitor.visit_bool(*x != 0),
+            DataValue::Int16(x) => visitor.visit_bool(*x != 0),
+            DataValue::Int8(x) => visitor.visit_bool(*x != 0),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    /// Unsigned bytes are stored in signed columns, so their bits are taken as they are.
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            DataValue::Int8(x) => visitor.visit_u8(*x as u8),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    /// Unsigned shorts are stored in signed columns, so their bits are taken as they are.
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            DataValue::Int16(x) => visitor.visit_u16(*x as u16),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            DataValue::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Unit variants are read from strings holding their name.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.0 {
+            DataValue::InlineString(x) | DataValue::OffsetString(x) => {
+                visitor.visit_enum(x.as_str().into_deserializer())
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
@@ -1,8 +1,11 @@
+use crate::encoding::CellText;
+use crate::pool::StringPoolWriter;
 use crate::{BcsvError, DataType, DataValue, Result};
 use byteorder::{ByteOrder, ReadBytesExt, WriteBytesExt};
-use std::io::{BufRead, Read, Seek, SeekFrom, Write};
+use std::io::{Read, Write};
 
 /// The definition of a column, as written in the BCSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ColumnDefinition {
     /// The name hash of the column.
     pub name: u32,
@@ -22,13 +25,22 @@ pub struct ColumnDefinition {
 
 impl ColumnDefinition {
     pub(crate) fn read<T: ByteOrder>(mut reader: impl Read) -> Result<Self> {
-        Ok(ColumnDefinition {
+        let result = ColumnDefinition {
             name: reader.read_u32::<T>()?,
             bitmask: reader.read_u32::<T>()?,
             offset: reader.read_u16::<T>()?,
             shift: reader.read_u8()?,
             ty: reader.read_u8()?.try_into()?,
-        })
+        };
+
+        // Only integers get shifted, every other type ignores the shift.
+        match result.ty {
+            DataType::Int32 | DataType::UInt32 | DataType::Int16 | DataType::Int8 => {
+                result.shift()?;
+            }
+            _ => {}
+        }
+        Ok(result)
     }
 
     pub(crate) fn write<T: ByteOrder>(&self, mut writer: impl Write) -> Result<()> {
@@ -40,89 +52,147 @@ impl ColumnDefinition {
         Ok(())
     }
 
+    /// Gets the bytes of this column inside of a row.
+    fn slot(&self, row_size: usize) -> Result<std::ops::Range<usize>> {
+        let start = self.offset as usize;
+        let end = start + self.ty.size();
+        if end > row_size {
+            Err(BcsvError::ColumnOutOfBounds {
+                name: self.name,
+                offset: self.offset,
+                row_size,
+            })
+        } else {
+            Ok(start..end)
+        }
+    }
+
+    /// Gets the shift of an integer column, which has to be smaller than the number of bits of its type.
+    fn shift(&self) -> Result<u32> {
+        if (self.shift as usize) < self.ty.size() * 8 {
+            Ok(self.shift as u32)
+        } else {
+            Err(BcsvError::InvalidShift {
+                name: self.name,
+                shift: self.shift,
+                ty: self.ty,
+            })
+        }
+    }
+
+    /// Reads the value of this column from a row, using its offset, bitmask and shift.
+    /// Offset strings are looked up in the given string pool.
     pub(crate) fn read_entry<T: ByteOrder>(
         &self,
-        mut reader: impl BufRead + Seek,
-        string_offset: u64,
+        row: &[u8],
+        pool: &[u8],
+        text: CellText,
     ) -> Result<DataValue> {
+        let data = &row[self.slot(row.len())?];
         match self.ty {
             DataType::Int32 => {
-                let value = reader.read_i32::<T>()?;
+                let value = T::read_i32(data);
                 Ok(DataValue::Int32(
-                    (value & self.bitmask as i32) >> self.shift,
+                    (value & self.bitmask as i32) >> self.shift()?,
                 ))
             }
             DataType::InlineString => {
-                let mut buffer = [0; 32];
-                reader.read_exact(&mut buffer)?;
-                let (text, _) = encoding_rs::SHIFT_JIS.decode_without_bom_handling(&buffer);
-                Ok(DataValue::InlineString(text.into_owned()))
+                let end = data.iter().position(|x| *x == 0).unwrap_or(data.len());
+                Ok(DataValue::InlineString(text.decode(&data[..end])?))
             }
-            DataType::Float => Ok(reader.read_f32::<T>()?.into()),
+            DataType::Float => Ok(T::read_f32(data).into()),
             DataType::UInt32 => {
-                let value = reader.read_u32::<T>()?;
-                Ok(DataValue::UInt32((value & self.bitmask) >> self.shift))
+                let value = T::read_u32(data);
+                Ok(DataValue::UInt32((value & self.bitmask) >> self.shift()?))
             }
             DataType::Int16 => {
-                let value = reader.read_i16::<T>()?;
+                let value = T::read_i16(data);
                 Ok(DataValue::Int16(
-                    (value & self.bitmask as i16) >> self.shift,
+                    (value & self.bitmask as i16) >> self.shift()?,
                 ))
             }
             DataType::Int8 => {
-                let value = reader.read_i8()?;
-                Ok(DataValue::Int8((value & self.bitmask as i8) >> self.shift))
+                let value = data[0] as i8;
+                Ok(DataValue::Int8(
+                    (value & self.bitmask as i8) >> self.shift()?,
+                ))
             }
             DataType::OffsetString => {
-                let offset = reader.read_u32::<T>()? as u64;
-
-                let position = reader.stream_position()?;
-                reader.seek(SeekFrom::Start(string_offset + offset))?;
-
-                let mut result = Vec::new();
-                reader.read_until(0, &mut result)?;
-
-                reader.seek(SeekFrom::Start(position))?;
-
-                let (text, _) = encoding_rs::SHIFT_JIS.decode_without_bom_handling(&result);
-                Ok(DataValue::OffsetString(text.into_owned()))
+                let offset = T::read_u32(data);
+                let bytes = pool
+                    .get(offset as usize..)
+                    .ok_or(BcsvError::StringOutOfBounds {
+                        row: text.row,
+                        column: text.column,
+                        offset,
+                    })?;
+                let end = bytes.iter().position(|x| *x == 0).unwrap_or(bytes.len());
+                Ok(DataValue::OffsetString(text.decode(&bytes[..end])?))
             }
             DataType::Null => Ok(DataValue::Null),
         }
     }
 
+    /// Reads the pool offset of this column from a row, if it is an offset string column.
+    pub(crate) fn string_offset<T: ByteOrder>(&self, row: &[u8]) -> Result<Option<u32>> {
+        match self.ty {
+            DataType::OffsetString => Ok(Some(T::read_u32(&row[self.slot(row.len())?]))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Writes a value into this column of a row.
+    /// Integers are merged into the bits selected by the bitmask,
+    /// so columns that share one storage word don't overwrite each other.
+    /// Offset strings reuse the `original` offset if it still holds the same string.
     pub(crate) fn write_entry<T: ByteOrder>(
         &self,
         value: &DataValue,
-        mut writer: impl Write,
-        pool: &mut Vec<u8>,
+        row: &mut [u8],
+        pool: &mut StringPoolWriter,
+        original: Option<u32>,
+        text: CellText,
     ) -> Result<()> {
+        let slot = self.slot(row.len())?;
+        let data = &mut row[slot];
         match value {
             DataValue::Int32(x) => {
-                let value = (*x << self.shift) & self.bitmask as i32;
-                writer.write_i32::<T>(value)?
+                let mask = self.bitmask as i32;
+                let value = (T::read_i32(data) & !mask) | ((*x << self.shift()?) & mask);
+                T::write_i32(data, value);
+            }
+            DataValue::InlineString(x) => {
+                let bytes = text.encode(x)?;
+                // One byte is left for the terminator, the rest of the slot is zeroed to pad the string.
+                if bytes.len() >= data.len() {
+                    return Err(BcsvError::InlineStringTooLong {
+                        row: text.row,
+                        column: text.column,
+                        length: bytes.len(),
+                    });
+                }
+                data[..bytes.len()].copy_from_slice(&bytes);
+                data[bytes.len()..].fill(0);
             }
-            DataValue::InlineString(_) => return Err(BcsvError::InlineStringUnsupported),
-            DataValue::Float(x) => writer.write_f32::<T>(*x)?,
+            DataValue::Float(x) => T::write_f32(data, *x),
             DataValue::UInt32(x) => {
-                let value = (*x << self.shift) & self.bitmask;
-                writer.write_u32::<T>(value)?
+                let mask = self.bitmask;
+                let value = (T::read_u32(data) & !mask) | ((*x << self.shift()?) & mask);
+                T::write_u32(data, value);
             }
             DataValue::Int16(x) => {
-                let value = (*x << self.shift) & self.bitmask as i16;
-                writer.write_i16::<T>(value)?
+                let mask = self.bitmask as i16;
+                let value = (T::read_i16(data) & !mask) | ((*x << self.shift()?) & mask);
+                T::write_i16(data, value);
             }
             DataValue::Int8(x) => {
-                let value = (*x << self.shift) & self.bitmask as i8;
-                writer.write_i8(value)?
+                let mask = self.bitmask as i8;
+                let value = (data[0] as i8 & !mask) | ((*x << self.shift()?) & mask);
+                data[0] = value as u8;
             }
             DataValue::OffsetString(x) => {
-                let (text, _, _) = encoding_rs::SHIFT_JIS.encode(x);
-
-                let offset = pool.len();
-                pool.extend_from_slice(&text);
-
-                writer.write_u32::<T>(offset as u32)?;
+                let bytes = text.encode(x)?;
+                T::write_u32(data, pool.insert(&bytes, original));
             }
             DataValue::Null => {}
         }
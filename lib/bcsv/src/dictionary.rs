@@ -0,0 +1,122 @@
+use crate::{NameHash, Result};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// A dictionary of known column names, used to turn name hashes back into names.
+/// Every name is hashed with both [`old_hash`](`crate::old_hash`) and [`jgadget_hash`](`crate::jgadget_hash`),
+/// so one dictionary works for GameCube and Wii files.
+#[derive(Debug, Clone, Default)]
+pub struct HashDictionary {
+    jgadget: HashMap<u32, String>,
+    old: HashMap<u32, String>,
+    names: BTreeSet<String>,
+}
+
+impl HashDictionary {
+    /// Creates an empty dictionary.
+    pub fn new() -> Self {
+        HashDictionary::default()
+    }
+
+    /// Reads a word list with one name per line.
+    /// Empty lines and lines starting with `#` are skipped.
+    pub fn read(reader: impl BufRead) -> Result<Self> {
+        let mut result = HashDictionary::new();
+        result.load(reader)?;
+        Ok(result)
+    }
+
+    /// Reads the word list at the given path, see [`HashDictionary::read`].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        HashDictionary::read(BufReader::new(file))
+    }
+
+    /// Adds every name of a word list to this dictionary, see [`HashDictionary::read`].
+    pub fn load(&mut self, reader: impl BufRead) -> Result<()> {
+        for line in reader.lines() {
+            let line = line?;
+            let name = line.trim();
+            if name.is_empty() || name.starts_with('#') {
+                continue;
+            }
+            self.insert(name);
+        }
+        Ok(())
+    }
+
+    /// Adds a name to this dictionary.
+    /// Names that were added first win the lookup if two names have the same hash,
+    /// but every name is kept.
+    pub fn insert(&mut self, name: &str) {
+        if !self.names.insert(name.into()) {
+            return;
+        }
+        self.jgadget
+            .entry(NameHash::Jgadget.hash(name))
+            .or_insert_with(|| name.into());
+        self.old
+            .entry(NameHash::Old.hash(name))
+            .or_insert_with(|| name.into());
+    }
+
+    /// Adds every name of another dictionary to this one.
+    pub fn merge(&mut self, other: HashDictionary) {
+        for (hash, name) in other.jgadget {
+            self.jgadget.entry(hash).or_insert(name);
+        }
+        for (hash, name) in other.old {
+            self.old.entry(hash).or_insert(name);
+        }
+        self.names.extend(other.names);
+    }
+
+    /// Looks up the name of a hash that was made with the given hash function.
+    pub fn name(&self, hash: u32, function: NameHash) -> Option<&str> {
+        match function {
+            NameHash::Jgadget => self.jgadget.get(&hash),
+            NameHash::Old => self.old.get(&hash),
+        }
+        .map(|x| x.as_str())
+    }
+
+    /// Looks up the name of a hash, trying [`NameHash::Jgadget`] first and [`NameHash::Old`] second.
+    pub fn get(&self, hash: u32) -> Option<&str> {
+        self.name(hash, NameHash::Jgadget)
+            .or_else(|| self.name(hash, NameHash::Old))
+    }
+
+    /// Iterates over every name in this dictionary in sorted order,
+    /// including names that lost the lookup to another name with the same hash.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|x| x.as_str())
+    }
+
+    /// Gets the number of names in this dictionary.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Checks if this dictionary has no names.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for HashDictionary {
+    fn extend<T: IntoIterator<Item = S>>(&mut self, iter: T) {
+        for name in iter {
+            self.insert(name.as_ref());
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for HashDictionary {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut result = HashDictionary::new();
+        result.extend(iter);
+        result
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{BcsvError, Result};
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// The text encoding used for the strings of a table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// Used by Nintendo's own games.
+    #[default]
+    ShiftJis,
+    /// Used by some later titles and fan translations.
+    Utf8,
+    /// Used by some fan translations of western releases.
+    Windows1252,
+}
+
+impl Encoding {
+    fn get(self) -> &'static encoding_rs::Encoding {
+        match self {
+            Encoding::ShiftJis => encoding_rs::SHIFT_JIS,
+            Encoding::Utf8 => encoding_rs::UTF_8,
+            Encoding::Windows1252 => encoding_rs::WINDOWS_1252,
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.get().name())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match encoding_rs::Encoding::for_label(s.as_bytes()) {
+            Some(x) if x == encoding_rs::SHIFT_JIS => Ok(Encoding::ShiftJis),
+            Some(x) if x == encoding_rs::UTF_8 => Ok(Encoding::Utf8),
+            Some(x) if x == encoding_rs::WINDOWS_1252 => Ok(Encoding::Windows1252),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Encodes and decodes the strings of one cell, so errors can point at it.
+#[derive(Clone, Copy)]
+pub(crate) struct CellText {
+    pub encoding: Encoding,
+    pub strict: bool,
+    pub row: usize,
+    pub column: usize,
+}
+
+impl CellText {
+    pub(crate) fn decode(&self, bytes: &[u8]) -> Result<String> {
+        let (text, errors) = self.encoding.get().decode_without_bom_handling(bytes);
+        if errors && self.strict {
+            return Err(self.error());
+        }
+        Ok(text.into_owned())
+    }
+
+    pub(crate) fn encode<'a>(&self, text: &'a str) -> Result<Cow<'a, [u8]>> {
+        let (bytes, _, errors) = self.encoding.get().encode(text);
+        if errors && self.strict {
+            return Err(self.error());
+        }
+        Ok(bytes)
+    }
+
+    fn error(&self) -> BcsvError {
+        BcsvError::UnmappableText {
+            row: self.row,
+            column: self.column,
+            encoding: self.encoding,
+        }
+    }
+}
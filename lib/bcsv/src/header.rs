@@ -1,5 +1,6 @@
-use byteorder::{ByteOrder, ReadBytesExt, WriteBytesExt};
-use std::io::{Read, Result, Write};
+use crate::{DataType, Endian};
+use byteorder::{BigEndian, ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Read, Result, Seek, SeekFrom, Write};
 
 pub struct BcsvHeader {
     pub row_count: u32,
@@ -27,4 +28,47 @@ impl BcsvHeader {
         writer.write_u32::<T>(self.row_size)?;
         Ok(())
     }
+
+    /// Guesses the byte order of a BCSV from the plausibility of its header.
+    /// The data has to start right after the column definitions and every column has to fit into a row.
+    /// The reader is moved back to where it started afterwards.
+    pub fn detect(mut reader: impl Read + Seek) -> Result<Option<Endian>> {
+        let start = reader.stream_position()?;
+        let result = if Self::plausible::<BigEndian>(&mut reader, start)? {
+            Some(Endian::Big)
+        } else if Self::plausible::<LittleEndian>(&mut reader, start)? {
+            Some(Endian::Little)
+        } else {
+            None
+        };
+
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(result)
+    }
+
+    fn plausible<T: ByteOrder>(mut reader: impl Read + Seek, start: u64) -> Result<bool> {
+        reader.seek(SeekFrom::Start(start))?;
+        let header = match Self::read::<T>(&mut reader) {
+            Ok(x) => x,
+            Err(_) => return Ok(false),
+        };
+        if header.column_count > u16::MAX as u32
+            || header.data_offset != 0x10 + header.column_count * 0x0C
+        {
+            return Ok(false);
+        }
+
+        let mut buffer = vec![0; header.column_count as usize * 0x0C];
+        if reader.read_exact(&mut buffer).is_err() {
+            return Ok(false);
+        }
+
+        Ok(buffer.chunks_exact(0x0C).all(|column| {
+            let offset = T::read_u16(&column[8..10]) as u32;
+            match DataType::try_from(column[11]) {
+                Ok(ty) => offset + ty.size() as u32 <= header.row_size,
+                Err(_) => false,
+            }
+        }))
+    }
 }
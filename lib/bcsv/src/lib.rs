@@ -3,14 +3,33 @@
 //! BCSV / JMap is a custom file format made by nintendo for GameCube and Wii games.
 //! It stores data in a table format with typed columns.
 
+mod crack;
 mod data;
+#[cfg(feature = "serde")]
+mod de;
 mod definition;
+mod dictionary;
+mod encoding;
 mod header;
+mod pool;
+mod reader;
+mod row;
+#[cfg(feature = "serde")]
+mod ser;
 mod table;
 
+pub use crack::{crack, crack_all, Collision, CrackOptions};
 pub use data::*;
 pub use definition::*;
+pub use dictionary::*;
+pub use encoding::Encoding;
+pub use endian::Endian;
+pub use reader::{RowRef, RowRefIter, TableReader};
+pub use row::*;
 pub use table::*;
+
+#[cfg(feature = "derive")]
+pub use bcsv_derive::BcsvRow;
 use thiserror::Error;
 
 /// Re-exports from the byteorder crate.
@@ -32,6 +51,26 @@ pub fn jgadget_hash(name: &[u8]) -> u32 {
     })
 }
 
+/// Selects the hash function that turns names into column hashes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NameHash {
+    /// Uses [`old_hash`], for GameCube games.
+    Old,
+    /// Uses [`jgadget_hash`], for Wii games.
+    #[default]
+    Jgadget,
+}
+
+impl NameHash {
+    /// Hashes a name with the selected function.
+    pub fn hash(self, name: &str) -> u32 {
+        match self {
+            NameHash::Old => old_hash(name.as_bytes()),
+            NameHash::Jgadget => jgadget_hash(name.as_bytes()),
+        }
+    }
+}
+
 type Result<T> = std::result::Result<T, BcsvError>;
 
 /// Errors that get returned from this library.
@@ -49,8 +88,55 @@ pub enum BcsvError {
         expected: DataType,
         actual: DataType,
     },
-    #[error("inline strings are depricated and encoding is unsupported. Use offset strings")]
-    InlineStringUnsupported,
+    #[error("the inline string in row {row} column {column} is {length} bytes long when encoded, but only 31 bytes fit before the terminator")]
+    InlineStringTooLong {
+        row: usize,
+        column: usize,
+        length: usize,
+    },
+    #[error("the string in row {row} column {column} can't be represented in {encoding}")]
+    UnmappableText {
+        row: usize,
+        column: usize,
+        encoding: Encoding,
+    },
     #[error("the table has too many columns, a maximum of 65536 columns is allowed")]
     TooManyColumns,
+    #[error(
+        "the column {name:#010x} at offset {offset:#x} doesn't fit into a row of {row_size} bytes"
+    )]
+    ColumnOutOfBounds {
+        name: u32,
+        offset: u16,
+        row_size: usize,
+    },
+    #[error("the {field} of the table end at {end:#x}, past the end of the {length:#x} byte file")]
+    HeaderOutOfBounds {
+        field: &'static str,
+        end: u64,
+        length: u64,
+    },
+    #[error("the table has {0} rows, but a row size of 0 bytes")]
+    EmptyRows(u32),
+    #[error("the column {name:#010x} shifts its {ty} values by {shift} bits, which is more than they have")]
+    InvalidShift { name: u32, shift: u8, ty: DataType },
+    #[error("the string in row {row} column {column} starts at {offset:#x}, past the end of the string pool")]
+    StringOutOfBounds {
+        row: usize,
+        column: usize,
+        offset: u32,
+    },
+    #[error("the value {value} can't be converted into {target} without changing it")]
+    InvalidConversion { value: String, target: String },
+    #[error("the table has no column with the name hash {0:#010x}")]
+    MissingColumn(u32),
+    #[error("the row {row} is out of bounds, the table has {rows} rows")]
+    RowOutOfBounds { row: usize, rows: usize },
+    #[error("the column {column} is out of bounds, the table has {columns} columns")]
+    ColumnIndexOutOfBounds { column: usize, columns: usize },
+    #[cfg(feature = "serde")]
+    #[error("{0}")]
+    Serde(String),
+    #[error("the byte order could not be detected, the header is not plausible in either order")]
+    UnknownByteOrder,
 }
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+/// Builds the string pool of a table while its rows are written.
+/// Every string is stored once, so rows with the same string share one offset.
+pub(crate) struct StringPoolWriter {
+    data: Vec<u8>,
+    offsets: HashMap<Vec<u8>, u32>,
+}
+
+impl StringPoolWriter {
+    /// Creates an empty pool.
+    pub(crate) fn new() -> Self {
+        StringPoolWriter {
+            data: Vec::new(),
+            offsets: HashMap::new(),
+        }
+    }
+
+    /// Creates a pool that starts out with the pool of a file that was read,
+    /// so its strings keep their offsets and are shared with new ones.
+    pub(crate) fn with_original(pool: &[u8]) -> Self {
+        let mut result = StringPoolWriter {
+            data: pool.to_vec(),
+            offsets: HashMap::new(),
+        };
+
+        let mut start = 0;
+        while let Some(end) = pool[start..].iter().position(|x| *x == 0) {
+            result
+                .offsets
+                .entry(pool[start..start + end].to_vec())
+                .or_insert(start as u32);
+            start += end + 1;
+        }
+
+        result
+    }
+
+    /// Gets the offset of a string, which is added to the pool if it isn't in there yet.
+    /// If `original` points at the same string it is used, so strings that were shared keep their offset.
+    pub(crate) fn insert(&mut self, text: &[u8], original: Option<u32>) -> u32 {
+        if let Some(offset) = original {
+            let start = offset as usize;
+            let end = start + text.len();
+            if self.data.get(start..end) == Some(text) && self.data.get(end) == Some(&0) {
+                return offset;
+            }
+        }
+
+        if let Some(offset) = self.offsets.get(text) {
+            return *offset;
+        }
+
+        let offset = self.data.len() as u32;
+        self.data.extend_from_slice(text);
+        self.data.push(0);
+        self.offsets.insert(text.to_vec(), offset);
+        offset
+    }
+
+    /// Gets the bytes of the pool.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
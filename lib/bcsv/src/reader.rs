@@ -0,0 +1,311 @@
+use crate::encoding::CellText;
+use crate::header::BcsvHeader;
+use crate::{BcsvError, ColumnDefinition, DataValue, Endian, ReadOptions, Result, Table};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::iter::FusedIterator;
+use std::ops::Range;
+use std::path::Path;
+
+/// The parts of a BCSV file, read without decoding any values.
+pub(crate) struct RawTable {
+    pub header: BcsvHeader,
+    pub fields: Vec<ColumnDefinition>,
+    /// The bytes between the column definitions and the rows.
+    pub gap: Vec<u8>,
+    pub rows: Vec<u8>,
+    /// The string pool and everything after it until the end of the file.
+    pub pool: Vec<u8>,
+}
+
+impl RawTable {
+    pub(crate) fn read<T: ByteOrder>(mut reader: impl BufRead + Seek) -> Result<Self> {
+        let start = reader.stream_position()?;
+        let length = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start))?;
+
+        let header = BcsvHeader::read::<T>(&mut reader)?;
+
+        // The sizes come from the header, so they are checked against the file before anything gets allocated.
+        // Offsets are relative to the start of the table, which doesn't have to be the start of the stream.
+        let data_offset = start + header.data_offset as u64;
+        let columns = 0x10 + header.column_count as u64 * 0x0C;
+        check_bounds("columns", start + columns, length)?;
+        let rows = header.row_count as u64 * header.row_size as u64;
+        check_bounds("rows", data_offset + rows, length)?;
+        // Empty rows take up no space, so their count isn't bounded by the file at all.
+        if header.row_size == 0 && header.row_count > 0 {
+            return Err(BcsvError::EmptyRows(header.row_count));
+        }
+
+        let mut fields = Vec::with_capacity(header.column_count as usize);
+        for _ in 0..header.column_count {
+            fields.push(ColumnDefinition::read::<T>(&mut reader)?);
+        }
+
+        let mut gap = Vec::new();
+        let position = reader.stream_position()?;
+        if data_offset > position {
+            gap.resize((data_offset - position) as usize, 0);
+            reader.read_exact(&mut gap)?;
+        }
+
+        let mut rows = vec![0; header.row_count as usize * header.row_size as usize];
+        reader.seek(SeekFrom::Start(data_offset))?;
+        reader.read_exact(&mut rows)?;
+
+        // The string pool follows the rows and runs until the end of the file.
+        let mut pool = Vec::new();
+        reader.read_to_end(&mut pool)?;
+
+        Ok(RawTable {
+            header,
+            fields,
+            gap,
+            rows,
+            pool,
+        })
+    }
+}
+
+/// Gets the byte order a [`ByteOrder`] type stands for, so values can be decoded without being generic.
+fn endian<T: ByteOrder>() -> Endian {
+    if T::read_u16(&[0, 1]) == 1 {
+        Endian::Big
+    } else {
+        Endian::Little
+    }
+}
+
+fn check_bounds(field: &'static str, end: u64, length: u64) -> Result<()> {
+    if end > length {
+        Err(BcsvError::HeaderOutOfBounds { field, end, length })
+    } else {
+        Ok(())
+    }
+}
+
+/// A read only BCSV table that only decodes values when they are accessed.
+/// The rows and string pool are kept as they were read, so scanning through many files stays fast and light on memory.
+/// Use [`Table`] to edit a table, or [`TableReader::to_table`] to turn this into one.
+pub struct TableReader {
+    fields: Vec<ColumnDefinition>,
+    row_count: usize,
+    row_size: usize,
+    rows: Vec<u8>,
+    pool: Vec<u8>,
+    endian: Endian,
+    options: ReadOptions,
+}
+
+impl TableReader {
+    /// Reads in a BCSV with the given byte order from the given reader, see [`Table::read`].
+    pub fn read<T: ByteOrder>(reader: impl BufRead + Seek) -> Result<Self> {
+        TableReader::read_with::<T>(reader, ReadOptions::default())
+    }
+
+    /// Reads in a BCSV with the given byte order from the given reader.
+    /// The options are used whenever a string is decoded.
+    pub fn read_with<T: ByteOrder>(
+        reader: impl BufRead + Seek,
+        options: ReadOptions,
+    ) -> Result<Self> {
+        let raw = RawTable::read::<T>(reader)?;
+
+        Ok(TableReader {
+            fields: raw.fields,
+            row_count: raw.header.row_count as usize,
+            row_size: raw.header.row_size as usize,
+            rows: raw.rows,
+            pool: raw.pool,
+            endian: endian::<T>(),
+            options,
+        })
+    }
+
+    /// Reads in a BCSV from the given reader, detecting its byte order from the header.
+    /// The detected order is returned as well, like [`Table::read_auto`] does.
+    pub fn read_auto(reader: impl BufRead + Seek) -> Result<(Self, Endian)> {
+        TableReader::read_auto_with(reader, ReadOptions::default())
+    }
+
+    /// Reads in a BCSV from the given reader, detecting its byte order, see [`TableReader::read_with`].
+    pub fn read_auto_with(
+        mut reader: impl BufRead + Seek,
+        options: ReadOptions,
+    ) -> Result<(Self, Endian)> {
+        let table = match BcsvHeader::detect(&mut reader)? {
+            Some(Endian::Big) => TableReader::read_with::<BigEndian>(reader, options)?,
+            Some(Endian::Little) => TableReader::read_with::<LittleEndian>(reader, options)?,
+            None => return Err(BcsvError::UnknownByteOrder),
+        };
+        let endian = table.endian;
+        Ok((table, endian))
+    }
+
+    /// Reads in the BCSV with the given byte order at the given path.
+    pub fn open<T: ByteOrder>(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        TableReader::read::<T>(BufReader::new(file))
+    }
+
+    /// Reads in the BCSV at the given path, detecting its byte order from the header.
+    pub fn open_auto(path: impl AsRef<Path>) -> Result<(Self, Endian)> {
+        let file = File::open(path)?;
+        TableReader::read_auto(BufReader::new(file))
+    }
+
+    /// Gets the byte order the table is encoded in.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Gets the column definitions of this table.
+    pub fn definitions(&self) -> &[ColumnDefinition] {
+        &self.fields
+    }
+
+    /// Gets the number of rows in this table.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Gets the number of columns in this table.
+    pub fn column_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Finds the column with the given name, see [`Table::column_by_name`].
+    pub fn column_by_name(&self, name: &str) -> Option<usize> {
+        crate::table::column_by_name(&self.fields, name)
+    }
+
+    /// Gets a row of this table, if it exists.
+    pub fn row(&self, row: usize) -> Option<RowRef<'_>> {
+        if row >= self.row_count {
+            return None;
+        }
+        let start = row * self.row_size;
+        let data = self.rows.get(start..start + self.row_size)?;
+        Some(RowRef {
+            reader: self,
+            index: row,
+            data,
+        })
+    }
+
+    /// Returns an iterator over the table's rows.
+    pub fn iter(&self) -> RowRefIter<'_> {
+        RowRefIter {
+            reader: self,
+            rows: 0..self.row_count(),
+        }
+    }
+
+    /// Decodes every value into a [`Table`] that can be edited.
+    pub fn to_table(&self) -> Result<Table> {
+        let mut table = Table::with_definitions(self.fields.clone());
+        for row in self.iter() {
+            table.push_row_unchecked(row.to_vec()?);
+        }
+        Ok(table)
+    }
+
+    fn decode(&self, row: usize, column: usize, data: &[u8]) -> Result<DataValue> {
+        let text = CellText {
+            encoding: self.options.encoding,
+            strict: self.options.strict,
+            row,
+            column,
+        };
+        let definition = &self.fields[column];
+        match self.endian {
+            Endian::Big => definition.read_entry::<BigEndian>(data, &self.pool, text),
+            Endian::Little => definition.read_entry::<LittleEndian>(data, &self.pool, text),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a TableReader {
+    type Item = RowRef<'a>;
+
+    type IntoIter = RowRefIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A row of a [`TableReader`], which decodes its values when they are accessed.
+#[derive(Clone, Copy)]
+pub struct RowRef<'a> {
+    reader: &'a TableReader,
+    index: usize,
+    data: &'a [u8],
+}
+
+impl<'a> RowRef<'a> {
+    /// Gets the index of this row in the table.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets the bytes of this row as they are stored in the file.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Decodes the value in the given column, if the column exists.
+    pub fn get(&self, column: usize) -> Option<Result<DataValue>> {
+        if column < self.reader.fields.len() {
+            Some(self.reader.decode(self.index, column, self.data))
+        } else {
+            None
+        }
+    }
+
+    /// Decodes the value in the column with the given name, see [`Table::column_by_name`].
+    pub fn get_by_name(&self, name: &str) -> Option<Result<DataValue>> {
+        self.get(self.reader.column_by_name(name)?)
+    }
+
+    /// Decodes every value of this row in column order.
+    pub fn values(&self) -> impl Iterator<Item = Result<DataValue>> + 'a {
+        let row = *self;
+        (0..self.reader.fields.len())
+            .map(move |column| row.reader.decode(row.index, column, row.data))
+    }
+
+    /// Decodes every value of this row into a vec.
+    pub fn to_vec(&self) -> Result<Vec<DataValue>> {
+        self.values().collect()
+    }
+}
+
+/// An iterator over the rows of a [`TableReader`].
+pub struct RowRefIter<'a> {
+    reader: &'a TableReader,
+    rows: Range<usize>,
+}
+
+impl<'a> Iterator for RowRefIter<'a> {
+    type Item = RowRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.row(self.rows.next()?)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for RowRefIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.reader.row(self.rows.next_back()?)
+    }
+}
+
+impl<'a> FusedIterator for RowRefIter<'a> {}
+impl<'a> ExactSizeIterator for RowRefIter<'a> {}
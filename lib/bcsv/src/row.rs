@@ -0,0 +1,217 @@
+use crate::{BcsvError, ColumnDefinition, DataType, DataValue, Result, Table};
+use std::fmt::Display;
+
+/// A struct with a fixed column layout, usually implemented with `#[derive(BcsvRow)]`.
+/// The derive macro is enabled with the `derive` feature.
+pub trait BcsvRow: Sized {
+    /// Creates the column definitions of this row, in field order.
+    fn definitions() -> Vec<ColumnDefinition>;
+
+    /// Creates a row from values in the order of [`BcsvRow::definitions`].
+    /// The slice has to hold one value per definition, values of a different type are converted.
+    /// Fails if a value can't be converted without changing it, see [`ColumnValue`].
+    fn from_row(row: &[DataValue]) -> Result<Self>;
+
+    /// Turns this row into values in the order of [`BcsvRow::definitions`].
+    fn to_row(&self) -> Result<Vec<DataValue>>;
+}
+
+/// A rust type that can be stored in a column of any type.
+/// Numbers are only converted if they keep their value and strings are formatted and parsed,
+/// otherwise [`BcsvError::InvalidConversion`] is returned.
+/// `u8` and `u16` are stored with the same bits in [`Int8`](`DataType::Int8`)
+/// and [`Int16`](`DataType::Int16`) columns, since those are the types they get by default.
+pub trait ColumnValue: Sized {
+    /// Converts a value of any type into this rust type.
+    fn from_value(value: &DataValue) -> Result<Self>;
+
+    /// Converts this into a value of the given type.
+    fn to_value(&self, ty: DataType) -> Result<DataValue>;
+}
+
+fn invalid_conversion(value: impl Display, target: impl Display) -> BcsvError {
+    BcsvError::InvalidConversion {
+        value: value.to_string(),
+        target: target.to_string(),
+    }
+}
+
+/// Converts a float into an integer, if it has no fractional part and is in range.
+fn float_to_integer(value: f32) -> Option<i64> {
+    let result = value as i64;
+    (result as f32 == value).then_some(result)
+}
+
+/// Converts an integer into a float, if the float can represent it exactly.
+fn integer_to_float(value: i64) -> Option<f32> {
+    let result = value as f32;
+    (result as i64 == value).then_some(result)
+}
+
+macro_rules! column_integer {
+    ($($ty:ty $(as $variant:ident($signed:ty))?),* $(,)?) => {
+        $(
+            impl ColumnValue for $ty {
+                #[allow(unreachable_patterns)]
+                fn from_value(value: &DataValue) -> Result<Self> {
+                    let result = match value {
+                        $(DataValue::$variant(x) => Some(*x as $ty),)?
+                        DataValue::Int32(x) => <$ty>::try_from(*x).ok(),
+                        DataValue::Float(x) => {
+                            float_to_integer(*x).and_then(|x| <$ty>::try_from(x).ok())
+                        }
+                        DataValue::UInt32(x) => <$ty>::try_from(*x).ok(),
+                        DataValue::Int16(x) => <$ty>::try_from(*x).ok(),
+                        DataValue::Int8(x) => <$ty>::try_from(*x).ok(),
+                        DataValue::InlineString(x) | DataValue::OffsetString(x) => {
+                            x.trim().parse().ok()
+                        }
+                        DataValue::Null => Some(Self::default()),
+                    };
+                    result.ok_or_else(|| invalid_conversion(value, stringify!($ty)))
+                }
+
+                #[allow(unreachable_patterns)]
+                fn to_value(&self, ty: DataType) -> Result<DataValue> {
+                    let result = match ty {
+                        $(DataType::$variant => Some(DataValue::$variant(*self as $signed)),)?
+                        DataType::Int32 => i32::try_from(*self).ok().map(DataValue::Int32),
+                        DataType::Float => integer_to_float(*self as i64).map(DataValue::Float),
+                        DataType::UInt32 => u32::try_from(*self).ok().map(DataValue::UInt32),
+                        DataType::Int16 => i16::try_from(*self).ok().map(DataValue::Int16),
+                        DataType::Int8 => i8::try_from(*self).ok().map(DataValue::Int8),
+                        DataType::InlineString => Some(DataValue::InlineString(self.to_string())),
+                        DataType::OffsetString => Some(DataValue::OffsetString(self.to_string())),
+                        DataType::Null => Some(DataValue::Null),
+                    };
+                    result.ok_or_else(|| invalid_conversion(self, ty))
+                }
+            }
+        )*
+    };
+}
+
+column_integer!(i32, u32, i16, u16 as Int16(i16), i8, u8 as Int8(i8));
+
+impl ColumnValue for f32 {
+    fn from_value(value: &DataValue) -> Result<Self> {
+        let result = match value {
+            DataValue::Int32(x) => integer_to_float(*x as i64),
+            DataValue::Float(x) => Some(*x),
+            DataValue::UInt32(x) => integer_to_float(*x as i64),
+            DataValue::Int16(x) => Some(*x as f32),
+            DataValue::Int8(x) => Some(*x as f32),
+            DataValue::InlineString(x) | DataValue::OffsetString(x) => x.trim().parse().ok(),
+            DataValue::Null => Some(0.0),
+        };
+        result.ok_or_else(|| invalid_conversion(value, "f32"))
+    }
+
+    fn to_value(&self, ty: DataType) -> Result<DataValue> {
+        let integer = float_to_integer(*self);
+        let result = match ty {
+            DataType::Int32 => integer
+                .and_then(|x| i32::try_from(x).ok())
+                .map(DataValue::Int32),
+            DataType::Float => Some(DataValue::Float(*self)),
+            DataType::UInt32 => integer
+                .and_then(|x| u32::try_from(x).ok())
+                .map(DataValue::UInt32),
+            DataType::Int16 => integer
+                .and_then(|x| i16::try_from(x).ok())
+                .map(DataValue::Int16),
+            DataType::Int8 => integer
+                .and_then(|x| i8::try_from(x).ok())
+                .map(DataValue::Int8),
+            DataType::InlineString => Some(DataValue::InlineString(self.to_string())),
+            DataType::OffsetString => Some(DataValue::OffsetString(self.to_string())),
+            DataType::Null => Some(DataValue::Null),
+        };
+        result.ok_or_else(|| invalid_conversion(self, ty))
+    }
+}
+
+/// Flags are stored as integers, so any integer that isn't zero is true.
+impl ColumnValue for bool {
+    fn from_value(value: &DataValue) -> Result<Self> {
+        match value {
+            DataValue::UInt32(x) => Ok(*x != 0),
+            x => i32::from_value(x).map(|x| x != 0),
+        }
+    }
+
+    fn to_value(&self, ty: DataType) -> Result<DataValue> {
+        i32::from(*self).to_value(ty)
+    }
+}
+
+impl ColumnValue for String {
+    fn from_value(value: &DataValue) -> Result<Self> {
+        match value {
+            DataValue::Null => Ok(String::new()),
+            x => Ok(x.to_string()),
+        }
+    }
+
+    fn to_value(&self, ty: DataType) -> Result<DataValue> {
+        let text = self.trim();
+        let result = match ty {
+            DataType::InlineString => Some(DataValue::InlineString(self.clone())),
+            DataType::OffsetString => Some(DataValue::OffsetString(self.clone())),
+            DataType::Int32 => text.parse().ok().map(DataValue::Int32),
+            DataType::Float => text.parse().ok().map(DataValue::Float),
+            DataType::UInt32 => text.parse().ok().map(DataValue::UInt32),
+            DataType::Int16 => text.parse().ok().map(DataValue::Int16),
+            DataType::Int8 => text.parse().ok().map(DataValue::Int8),
+            DataType::Null => Some(DataValue::Null),
+        };
+        result.ok_or_else(|| invalid_conversion(format_args!("{self:?}"), ty))
+    }
+}
+
+impl Table {
+    /// Creates a new table with the exact column layout of the given row type.
+    pub fn from_rows<R: BcsvRow>(rows: &[R]) -> Result<Table> {
+        let mut table = Table::with_definitions(R::definitions());
+        for row in rows {
+            table.push_row_unchecked(row.to_row()?);
+        }
+        Ok(table)
+    }
+
+    /// Reads every row of this table as the given row type.
+    /// Columns are matched by their name and have to have the type the row expects,
+    /// any other columns of the table are ignored.
+    pub fn rows<R: BcsvRow>(&self) -> Result<Vec<R>> {
+        let columns = R::definitions()
+            .iter()
+            .map(|definition| {
+                let column = self
+                    .definitions()
+                    .iter()
+                    .position(|x| x.name == definition.name)
+                    .ok_or(BcsvError::MissingColumn(definition.name))?;
+
+                let ty = self.definitions()[column].ty;
+                if ty != definition.ty {
+                    return Err(BcsvError::InvaildRowType {
+                        column,
+                        expected: definition.ty,
+                        actual: ty,
+                    });
+                }
+
+                Ok(column)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut values = Vec::with_capacity(columns.len());
+        self.iter()
+            .map(|row| {
+                values.clear();
+                values.extend(columns.iter().map(|x| row[*x].clone()));
+                R::from_row(&values)
+            })
+            .collect()
+    }
+}
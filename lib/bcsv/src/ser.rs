@@ -0,0 +1,459 @@
+use crate::{BcsvError, DataType, DataValue, NameHash, Result, Table};
+use serde::ser::{self, Impossible, Serialize, SerializeStruct};
+use std::fmt::Display;
+
+impl ser::Error for BcsvError {
+    fn custom<T: Display>(msg: T) -> Self {
+        BcsvError::Serde(msg.to_string())
+    }
+}
+
+impl Table {
+    /// Creates a new table from serializable structs, with one column per field.
+    /// Column names are the hashes of the field names, see [`NameHash::hash`].
+    /// The type of each column is taken from the first row where that field isn't `None`:
+    /// `i8`, `i16`, `i32` and `u32` map to their own types, `u8` and `u16` are stored with the same bits
+    /// in [`Int8`](`DataType::Int8`) and [`Int16`](`DataType::Int16`) columns like the derive macro does,
+    /// `bool` becomes an [`Int32`](`DataType::Int32`), floats become [`Float`](`DataType::Float`)s
+    /// and strings or unit enum variants become [`OffsetString`](`DataType::OffsetString`)s.
+    pub fn serialize_rows<S: Serialize>(rows: &[S], hash: NameHash) -> Result<Table> {
+        let rows = rows.iter().map(serialize_row).collect::<Result<Vec<_>>>()?;
+
+        let mut table = Table::new();
+        let names = match rows.first() {
+            Some(row) => row.iter().map(|x| x.name).collect::<Vec<_>>(),
+            None => return Ok(table),
+        };
+
+        for (column, name) in names.iter().enumerate() {
+            let ty = rows
+                .iter()
+                .map(|row| row[column].value.ty())
+                .find(|ty| *ty != DataType::Null)
+                .unwrap_or(DataType::Null);
+            table.push_column(hash.hash(name), ty, &ty.default_value())?;
+        }
+
+        for row in rows {
+            let fields = row.iter().map(|x| x.name);
+            if !fields.eq(names.iter().copied()) {
+                return Err(BcsvError::Serde(
+                    "every row has to serialize the same fields in the same order".into(),
+                ));
+            }
+
+            let mut values = row
+                .into_iter()
+                .zip(table.definitions())
+                .enumerate()
+                .map(|(column, (field, definition))| convert(field, definition.ty, column))
+                .collect::<Result<Vec<_>>>()?;
+            table.push_row(&mut values)?;
+        }
+
+        Ok(table)
+    }
+
+    /// Replaces the rows of this table with serializable structs, keeping the existing columns.
+    /// Fields are matched to columns by the hash of their name, see [`NameHash::hash`],
+    /// and their values are converted into the type of that column.
+    /// Columns without a matching field are filled with their default value.
+    pub fn set_rows<S: Serialize>(&mut self, rows: &[S], hash: NameHash) -> Result<()> {
+        let mut result = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut values = self
+                .definitions()
+                .iter()
+                .map(|x| x.ty.default_value())
+                .collect::<Vec<_>>();
+
+            for field in serialize_row(row)? {
+                let name = field.name;
+                let hash = hash.hash(name);
+                let column = self
+                    .definitions()
+                    .iter()
+                    .position(|x| x.name == hash)
+                    .ok_or_else(|| {
+                        BcsvError::Serde(format!("the table has no column for the field {name}"))
+                    })?;
+                values[column] = convert(field, self.definitions()[column].ty, column)?;
+            }
+
+            result.push(values);
+        }
+
+        self.clear_rows();
+        for mut values in result {
+            self.push_row(&mut values)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A serialized field of a row.
+struct Field {
+    name: &'static str,
+    value: DataValue,
+    /// Whether the value is a `u8` or `u16`, which is stored with the bits of a signed type.
+    unsigned: bool,
+}
+
+fn serialize_row<S: Serialize>(row: &S) -> Result<Vec<Field>> {
+    row.serialize(RowSerializer)
+}
+
+/// Converts a serialized value into the type of its column.
+/// Integers are range checked, a missing value becomes the default of the column.
+fn convert(field: Field, ty: DataType, column: usize) -> Result<DataValue> {
+    let value = field.value;
+    if value.ty() == ty {
+        return Ok(value);
+    }
+
+    let integer = match value {
+        DataValue::Int16(x) if field.unsigned => x as u16 as i64,
+        DataValue::Int8(x) if field.unsigned => x as u8 as i64,
+        DataValue::Int32(x) => x as i64,
+        DataValue::UInt32(x) => x as i64,
+        DataValue::Int16(x) => x as i64,
+        DataValue::Int8(x) => x as i64,
+        DataValue::Null => return Ok(ty.default_value()),
+        DataValue::OffsetString(x) if ty == DataType::InlineString => {
+            return Ok(DataValue::InlineString(x))
+        }
+        DataValue::InlineString(x) if ty == DataType::OffsetString => {
+            return Ok(DataValue::OffsetString(x))
+        }
+        _ => {
+            return Err(BcsvError::InvaildRowType {
+                column,
+                expected: ty,
+                actual: value.ty(),
+            })
+        }
+    };
+
+    let out_of_range = || {
+        BcsvError::Serde(format!(
+            "the value {integer} doesn't fit into column {column} of type {ty}"
+        ))
+    };
+
+    Ok(match ty {
+        DataType::Int32 => DataValue::Int32(integer.try_into().map_err(|_| out_of_range())?),
+        DataType::UInt32 => DataValue::UInt32(integer.try_into().map_err(|_| out_of_range())?),
+        DataType::Int16 => DataValue::Int16(integer.try_into().map_err(|_| out_of_range())?),
+        DataType::Int8 => DataValue::Int8(integer.try_into().map_err(|_| out_of_range())?),
+        DataType::Float => DataValue::Float(integer as f32),
+        _ => {
+            return Err(BcsvError::InvaildRowType {
+                column,
+                expected: ty,
+                actual: value.ty(),
+            })
+        }
+    })
+}
+
+fn unsupported(what: &str) -> BcsvError {
+    BcsvError::Serde(format!("{what} can't be stored in a BCSV"))
+}
+
+/// Serializes a struct into its named values.
+struct RowSerializer;
+
+/// Collects the fields of a struct.
+struct RowFields(Vec<Field>);
+
+impl SerializeStruct for RowFields {
+    type Ok = Vec<Field>;
+    type Error = BcsvError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        let mut unsigned = false;
+        let value = value.serialize(ValueSerializer {
+            unsigned: &mut unsigned,
+        })?;
+        self.0.push(Field {
+            name: key,
+            value,
+            unsigned,
+        });
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.0)
+    }
+}
+
+macro_rules! unsupported_row {
+    ($($method:ident($($arg:ty),*)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok> {
+                Err(unsupported("a row that isn't a struct"))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for RowSerializer {
+    type Ok = Vec<Field>;
+    type Error = BcsvError;
+    type SerializeSeq = Impossible<Self::Ok, BcsvError>;
+    type SerializeTuple = Impossible<Self::Ok, BcsvError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, BcsvError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, BcsvError>;
+    type SerializeMap = Impossible<Self::Ok, BcsvError>;
+    type SerializeStruct = RowFields;
+    type SerializeStructVariant = Impossible<Self::Ok, BcsvError>;
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        Ok(RowFields(Vec::with_capacity(len)))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok> {
+        value.serialize(self)
+    }
+
+    unsupported_row! {
+        serialize_bool(bool), serialize_i8(i8), serialize_i16(i16), serialize_i32(i32),
+        serialize_i64(i64), serialize_u8(u8), serialize_u16(u16), serialize_u32(u32),
+        serialize_u64(u64), serialize_f32(f32), serialize_f64(f64), serialize_char(char),
+        serialize_str(&str), serialize_bytes(&[u8]), serialize_none(), serialize_unit(),
+        serialize_unit_struct(&'static str),
+        serialize_unit_variant(&'static str, u32, &'static str),
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok> {
+        Err(unsupported("a row that isn't a struct"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(unsupported("a row that isn't a struct"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported("a row that isn't a struct"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("a row that isn't a struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("a row that isn't a struct"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(unsupported("a row that isn't a struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported("a row that isn't a struct"))
+    }
+}
+
+/// Serializes a single field into a [`DataValue`], marking unsigned values that are stored as signed ones.
+struct ValueSerializer<'a> {
+    unsigned: &'a mut bool,
+}
+
+impl<'a> ser::Serializer for ValueSerializer<'a> {
+    type Ok = DataValue;
+    type Error = BcsvError;
+    type SerializeSeq = Impossible<DataValue, BcsvError>;
+    type SerializeTuple = Impossible<DataValue, BcsvError>;
+    type SerializeTupleStruct = Impossible<DataValue, BcsvError>;
+    type SerializeTupleVariant = Impossible<DataValue, BcsvError>;
+    type SerializeMap = Impossible<DataValue, BcsvError>;
+    type SerializeStruct = Impossible<DataValue, BcsvError>;
+    type SerializeStructVariant = Impossible<DataValue, BcsvError>;
+
+    fn serialize_bool(self, v: bool) -> Result<DataValue> {
+        Ok(DataValue::Int32(v.into()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<DataValue> {
+        Ok(DataValue::Int8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<DataValue> {
+        Ok(DataValue::Int16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<DataValue> {
+        Ok(DataValue::Int32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<DataValue> {
+        i32::try_from(v)
+            .map(DataValue::Int32)
+            .map_err(|_| BcsvError::Serde(format!("{v} doesn't fit into a 32-bit column")))
+    }
+
+    /// Stored with the same bits in a signed column, like the derive macro does.
+    fn serialize_u8(self, v: u8) -> Result<DataValue> {
+        *self.unsigned = true;
+        Ok(DataValue::Int8(v as i8))
+    }
+
+    /// Stored with the same bits in a signed column, like the derive macro does.
+    fn serialize_u16(self, v: u16) -> Result<DataValue> {
+        *self.unsigned = true;
+        Ok(DataValue::Int16(v as i16))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<DataValue> {
+        Ok(DataValue::UInt32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<DataValue> {
+        u32::try_from(v)
+            .map(DataValue::UInt32)
+            .map_err(|_| BcsvError::Serde(format!("{v} doesn't fit into a 32-bit column")))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<DataValue> {
+        Ok(DataValue::Float(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<DataValue> {
+        Ok(DataValue::Float(v as f32))
+    }
+
+    fn serialize_char(self, v: char) -> Result<DataValue> {
+        Ok(DataValue::OffsetString(v.into()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<DataValue> {
+        Ok(DataValue::OffsetString(v.into()))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<DataValue> {
+        Err(unsupported("a byte array"))
+    }
+
+    fn serialize_none(self) -> Result<DataValue> {
+        Ok(DataValue::Null)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<DataValue> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<DataValue> {
+        Ok(DataValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<DataValue> {
+        Ok(DataValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<DataValue> {
+        Ok(DataValue::OffsetString(variant.into()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<DataValue> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<DataValue> {
+        Err(unsupported("an enum variant with data"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(unsupported("a sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(unsupported("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(unsupported("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported("an enum variant with data"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(unsupported("a map"))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(unsupported("a nested struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported("an enum variant with data"))
+    }
+}
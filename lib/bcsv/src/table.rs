@@ -1,26 +1,177 @@
+use crate::encoding::CellText;
 use crate::header::BcsvHeader;
-use crate::{BcsvError, ColumnDefinition, DataType, DataValue, Result};
-use byteorder::{ByteOrder, WriteBytesExt};
+use crate::pool::StringPoolWriter;
+use crate::reader::RawTable;
+use crate::{
+    BcsvError, ColumnDefinition, DataType, DataValue, Encoding, Endian, HashDictionary, NameHash,
+    Result,
+};
+use byteorder::{BigEndian, ByteOrder, LittleEndian, WriteBytesExt};
+use std::cmp::Ordering;
 use std::fs::File;
 use std::io::{BufRead, BufReader, BufWriter, Seek, Write};
 use std::iter::FusedIterator;
 use std::ops::{Index, IndexMut};
 use std::path::Path;
 
+/// How the string pool is laid out when a table is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StringPool {
+    /// Every string is stored once, in the order the rows use them.
+    #[default]
+    Deduplicate,
+    /// The pool of the file the table was read from is written as it was,
+    /// unchanged strings keep their offsets and new strings are appended.
+    /// Tables that weren't edited are written byte for byte as they were read.
+    Preserve,
+}
+
+/// Options that change how a table is read.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadOptions {
+    pub encoding: Encoding,
+    /// Fails with [`BcsvError::UnmappableText`] on strings that aren't valid in the encoding,
+    /// instead of replacing the invalid bytes.
+    pub strict: bool,
+}
+
+/// Options that change how a table is written.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WriteOptions {
+    pub string_pool: StringPool,
+    pub encoding: Encoding,
+    /// Fails with [`BcsvError::UnmappableText`] on strings that can't be encoded,
+    /// instead of replacing the characters.
+    pub strict: bool,
+    /// Writes the header values, unused row bits, string pool and trailing bytes of the file that was read as they were,
+    /// which implies [`StringPool::Preserve`]. Edited values are still written, so only the bytes they touch change.
+    pub lossless: bool,
+}
+
+/// Everything that was read from a file besides the values, so it can be written back for [`WriteOptions::lossless`].
+#[derive(Default)]
+struct Original {
+    /// The data offset from the header, which can leave room after the column definitions.
+    data_offset: u32,
+    /// The row size from the header, which can be larger than the columns need.
+    row_size: usize,
+    /// The bytes between the column definitions and the rows.
+    gap: Vec<u8>,
+    /// The rows as they were read, including bits no column covers.
+    rows: Vec<u8>,
+    /// The string pool that was read, without its padding.
+    pool: Vec<u8>,
+    /// The pool offset every value was read from, for [`StringPool::Preserve`].
+    offsets: Vec<Option<u32>>,
+    /// What followed the string pool.
+    trailer: Trailer,
+}
+
+enum Trailer {
+    /// The file was padded to 32 bytes with this byte.
+    Padding(u8),
+    /// Anything else, which is written back as it is.
+    Data(Vec<u8>),
+}
+
+impl Default for Trailer {
+    fn default() -> Self {
+        Trailer::Padding(b'@')
+    }
+}
+
+impl Original {
+    fn remove_row(&mut self, row: usize, columns: usize) {
+        let start = row * columns;
+        if start + columns <= self.offsets.len() {
+            self.offsets.drain(start..start + columns);
+        }
+        let start = row * self.row_size;
+        if start + self.row_size <= self.rows.len() {
+            self.rows.drain(start..start + self.row_size);
+        }
+    }
+
+    fn clear_rows(&mut self) {
+        self.offsets.clear();
+        self.rows.clear();
+    }
+
+    /// Makes sure rows that were added since reading have a place, so rows can be moved around.
+    fn fill(&mut self, rows: usize, columns: usize) {
+        if !self.offsets.is_empty() {
+            self.offsets.resize(rows * columns, None);
+        }
+        if !self.rows.is_empty() {
+            self.rows.resize(rows * self.row_size, 0);
+        }
+    }
+
+    fn insert_row(&mut self, row: usize, rows: usize, columns: usize) {
+        self.fill(rows, columns);
+        if !self.offsets.is_empty() {
+            let start = row * columns;
+            self.offsets.splice(start..start, vec![None; columns]);
+        }
+        if !self.rows.is_empty() {
+            let start = row * self.row_size;
+            self.rows.splice(start..start, vec![0; self.row_size]);
+        }
+    }
+
+    fn swap_rows(&mut self, a: usize, b: usize, rows: usize, columns: usize) {
+        self.fill(rows, columns);
+        for column in 0..columns.min(self.offsets.len()) {
+            self.offsets
+                .swap(a * columns + column, b * columns + column);
+        }
+        for byte in 0..self.row_size.min(self.rows.len()) {
+            self.rows
+                .swap(a * self.row_size + byte, b * self.row_size + byte);
+        }
+    }
+
+    fn reorder_rows(&mut self, order: &[usize], rows: usize, columns: usize) {
+        self.fill(rows, columns);
+        if !self.offsets.is_empty() {
+            self.offsets = order
+                .iter()
+                .flat_map(|x| &self.offsets[x * columns..(x + 1) * columns])
+                .copied()
+                .collect();
+        }
+        if !self.rows.is_empty() {
+            let size = self.row_size;
+            self.rows = order
+                .iter()
+                .flat_map(|x| &self.rows[x * size..(x + 1) * size])
+                .copied()
+                .collect();
+        }
+    }
+}
+
 /// A BCSV table that can be read, edited and saved.
 /// BCSV is a typed format, so each column has a concrete type and this is where [`DataType`] adn [`DataValue`] come into play.
 /// The table takes and returns [`DataValue`]s when editing and enfoces the correct type of each column.
 pub struct Table {
     fields: Vec<ColumnDefinition>,
     table: Vec<DataValue>,
+    original: Original,
 }
 
 impl Table {
     /// Creates a new empty BCSV table that can later be saved using [`Table::save`]
     pub fn new() -> Self {
+        Table::with_definitions(Vec::new())
+    }
+
+    /// Creates an empty table with the given columns, which are used as they are.
+    pub(crate) fn with_definitions(fields: Vec<ColumnDefinition>) -> Self {
         Table {
-            fields: Vec::new(),
+            fields,
             table: Vec::new(),
+            original: Original::default(),
         }
     }
 
@@ -28,25 +179,81 @@ impl Table {
     /// The reader has to be buffered and support seeking.
     /// For files just wrap your [`File`](`std::fs::File`) in a [`BufReader`](`std::io::BufReader`).
     /// For byte slices, wrap your `&[u8]` in a [`Cursor`](`std::io::Cursor`) to make is seekable.
-    pub fn read<T: ByteOrder>(mut reader: impl BufRead + Seek) -> Result<Self> {
-        let header = BcsvHeader::read::<T>(&mut reader)?;
+    pub fn read<T: ByteOrder>(reader: impl BufRead + Seek) -> Result<Self> {
+        Table::read_with::<T>(reader, ReadOptions::default())
+    }
 
-        let mut fields = Vec::with_capacity(header.column_count as usize);
-        for _ in 0..header.column_count {
-            fields.push(ColumnDefinition::read::<T>(&mut reader)?);
+    /// Reads in a BCSV from the given reader, see [`Table::read`].
+    pub fn read_with<T: ByteOrder>(
+        reader: impl BufRead + Seek,
+        options: ReadOptions,
+    ) -> Result<Self> {
+        let RawTable {
+            header,
+            fields,
+            gap,
+            rows: data,
+            mut pool,
+        } = RawTable::read::<T>(reader)?;
+        let row_size = header.row_size as usize;
+
+        // Up to 31 of the same byte that end the file on a 32 byte boundary are padding.
+        let size = header.data_offset as usize + data.len() + pool.len();
+        let is_padding = |x: &[u8]| x.len() < 32 && size & 31 == 0 && x.iter().all(|y| *y == x[0]);
+
+        let end = pool.iter().rposition(|x| *x == 0).map_or(0, |x| x + 1);
+        let mut trailer = pool.split_off(end);
+
+        let cells = header.row_count as usize * fields.len();
+        let mut table = Vec::with_capacity(cells);
+        let mut offsets = Vec::with_capacity(cells);
+        for index in 0..header.row_count as usize {
+            let row = &data[index * row_size..(index + 1) * row_size];
+            for (column, definition) in fields.iter().enumerate() {
+                let text = CellText {
+                    encoding: options.encoding,
+                    strict: options.strict,
+                    row: index,
+                    column,
+                };
+                table.push(definition.read_entry::<T>(row, &pool, text)?);
+                offsets.push(definition.string_offset::<T>(row)?);
+            }
         }
 
-        let size = header.row_count as usize * header.column_count as usize;
-        let string_offset = header.data_offset as u64 + size as u64;
-
-        let mut table = Vec::with_capacity(size);
-        for _ in 0..header.row_count {
-            for definition in &fields {
-                table.push(definition.read_entry::<T>(&mut reader, string_offset)?);
+        // Zero padding is split off with the pool, since it ends in a NUL as well.
+        // It starts after the terminator of the last string, unless a value points past that.
+        if trailer.is_empty() {
+            let start = pool
+                .iter()
+                .rposition(|x| *x != 0)
+                .map_or(1, |x| x + 2)
+                .min(pool.len());
+            if is_padding(&pool[start..]) && offsets.iter().flatten().all(|x| (*x as usize) < start)
+            {
+                trailer = pool.split_off(start);
             }
         }
 
-        Ok(Table { fields, table })
+        let trailer = if !trailer.is_empty() && is_padding(&trailer) {
+            Trailer::Padding(trailer[0])
+        } else {
+            Trailer::Data(trailer)
+        };
+
+        Ok(Table {
+            fields,
+            table,
+            original: Original {
+                data_offset: header.data_offset,
+                row_size,
+                gap,
+                rows: data,
+                pool,
+                offsets,
+                trailer,
+            },
+        })
     }
 
     /// Reads in the BCSV at the given path.
@@ -56,42 +263,145 @@ impl Table {
         Table::read::<T>(reader)
     }
 
+    /// Reads in the BCSV at the given path, see [`Table::read_with`].
+    pub fn open_with<T: ByteOrder>(path: impl AsRef<Path>, options: ReadOptions) -> Result<Self> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        Table::read_with::<T>(reader, options)
+    }
+
+    /// Reads in a BCSV from the given reader, detecting its byte order from the header.
+    /// Returns the table together with the byte order it was encoded in, so it can be saved the same way.
+    pub fn read_auto(reader: impl BufRead + Seek) -> Result<(Self, Endian)> {
+        Table::read_auto_with(reader, ReadOptions::default())
+    }
+
+    /// Reads in a BCSV from the given reader, detecting its byte order, see [`Table::read_auto`].
+    pub fn read_auto_with(
+        mut reader: impl BufRead + Seek,
+        options: ReadOptions,
+    ) -> Result<(Self, Endian)> {
+        match BcsvHeader::detect(&mut reader)? {
+            Some(Endian::Big) => Ok((Table::read_with::<BigEndian>(reader, options)?, Endian::Big)),
+            Some(Endian::Little) => Ok((
+                Table::read_with::<LittleEndian>(reader, options)?,
+                Endian::Little,
+            )),
+            None => Err(BcsvError::UnknownByteOrder),
+        }
+    }
+
+    /// Reads in the BCSV at the given path, detecting its byte order from the header.
+    pub fn open_auto(path: impl AsRef<Path>) -> Result<(Self, Endian)> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        Table::read_auto(reader)
+    }
+
+    /// Reads in the BCSV at the given path, detecting its byte order, see [`Table::read_auto`].
+    pub fn open_auto_with(path: impl AsRef<Path>, options: ReadOptions) -> Result<(Self, Endian)> {
+        let file = File::open(path)?;
+        let reader = BufReader::new(file);
+        Table::read_auto_with(reader, options)
+    }
+
     /// Write this table as a BCSV into the given writer.
     /// The writer is not buffered, but it is highly recomended to do so for files.
     /// Just wrap your [`File`](`std::fs::File`) in a [`BufWriter`](`std::io::BufWriter`).
-    pub fn write<T: ByteOrder>(&self, mut writer: impl Write) -> Result<()> {
-        let header = BcsvHeader {
+    pub fn write<T: ByteOrder>(&self, writer: impl Write) -> Result<()> {
+        self.write_with::<T>(writer, WriteOptions::default())
+    }
+
+    /// Write this table as a BCSV into the given writer, see [`Table::write`].
+    pub fn write_with<T: ByteOrder>(
+        &self,
+        mut writer: impl Write,
+        options: WriteOptions,
+    ) -> Result<()> {
+        let original = &self.original;
+        let mut header = BcsvHeader {
             row_count: self.row_count() as u32,
             column_count: self.fields.len() as u32,
             data_offset: 0x10 + self.fields.len() as u32 * 0x0C,
-            row_size: self.fields.iter().map(|x| x.ty.size() as u32).sum(),
+            row_size: self.row_size() as u32,
         };
+        let mut gap = Vec::new();
+        if options.lossless {
+            if original.data_offset > header.data_offset {
+                gap = original.gap.clone();
+                gap.resize((original.data_offset - header.data_offset) as usize, 0);
+                header.data_offset = original.data_offset;
+            }
+            header.row_size = header.row_size.max(original.row_size as u32);
+        }
         header.write::<T>(&mut writer)?;
 
         for definition in &self.fields {
             definition.write::<T>(&mut writer)?;
         }
-
-        let mut pool = Vec::new();
-        for row in 0..header.row_count {
-            for (column, definition) in self.fields.iter().enumerate() {
-                definition.write_entry::<T>(
-                    &self.table[row as usize * self.fields.len() + column],
-                    &mut writer,
-                    &mut pool,
-                )?;
+        writer.write_all(&gap)?;
+
+        let (mut pool, offsets) = match options.string_pool {
+            StringPool::Deduplicate if !options.lossless => (StringPoolWriter::new(), &[][..]),
+            _ => (
+                StringPoolWriter::with_original(&original.pool),
+                &original.offsets[..],
+            ),
+        };
+        let row_size = header.row_size as usize;
+        let mut buffer = vec![0; row_size];
+        for index in 0..self.row_count() {
+            let row = &self[index];
+            // Rows start out as they were read, so bits no column covers are kept.
+            let start = index * row_size;
+            match original.rows.get(start..start + row_size) {
+                Some(x) if options.lossless && original.row_size == row_size => {
+                    buffer.copy_from_slice(x)
+                }
+                _ => buffer.fill(0),
+            }
+            for (column, (definition, value)) in self.fields.iter().zip(row).enumerate() {
+                if definition.ty != value.ty() {
+                    return Err(BcsvError::InvaildRowType {
+                        column,
+                        expected: definition.ty,
+                        actual: value.ty(),
+                    });
+                }
+                let original = offsets
+                    .get(index * self.fields.len() + column)
+                    .copied()
+                    .flatten();
+                let text = CellText {
+                    encoding: options.encoding,
+                    strict: options.strict,
+                    row: index,
+                    column,
+                };
+                definition.write_entry::<T>(value, &mut buffer, &mut pool, original, text)?;
             }
+            writer.write_all(&buffer)?;
         }
 
-        writer.write_all(&pool)?;
+        let pool = pool.as_bytes();
+        writer.write_all(pool)?;
 
-        let size = header.data_offset as usize
-            + (header.row_count as usize * header.row_size as usize)
-            + pool.len();
-        let padding = ((size + 31) & !31) - size;
+        let padding = match &original.trailer {
+            Trailer::Padding(x) if options.lossless => Some(*x),
+            Trailer::Data(x) if options.lossless => {
+                writer.write_all(x)?;
+                None
+            }
+            _ => Some(b'@'),
+        };
 
-        for _ in 0..padding {
-            writer.write_u8(b'@')?;
+        if let Some(padding) = padding {
+            let size = header.data_offset as usize
+                + (header.row_count as usize * header.row_size as usize)
+                + pool.len();
+            for _ in size..((size + 31) & !31) {
+                writer.write_u8(padding)?;
+            }
         }
 
         Ok(())
@@ -104,27 +414,64 @@ impl Table {
         self.write::<T>(writer)
     }
 
+    /// Saves this BCSV to the given path, see [`Table::write_with`].
+    pub fn save_with<T: ByteOrder>(
+        &self,
+        path: impl AsRef<Path>,
+        options: WriteOptions,
+    ) -> Result<()> {
+        let file = File::create(path)?;
+        let writer = BufWriter::new(file);
+        self.write_with::<T>(writer, options)
+    }
+
     /// Appends a row of values onto this table.
     /// The values will be moved out of the given vec on success.
     pub fn push_row(&mut self, values: &mut Vec<DataValue>) -> Result<()> {
+        self.check_row(values)?;
+        self.table.append(values);
+        Ok(())
+    }
+
+    /// Inserts a row of values at the given index, moving every row after it down.
+    /// The values will be moved out of the given vec on success.
+    /// Fails if the index is greater than the number of rows.
+    pub fn insert_row(&mut self, row: usize, values: &mut Vec<DataValue>) -> Result<()> {
+        let rows = self.row_count();
+        if row > rows {
+            return Err(BcsvError::RowOutOfBounds { row, rows });
+        }
+        self.check_row(values)?;
+
+        let start = row * self.fields.len();
+        self.table.splice(start..start, values.drain(..));
+        self.original.insert_row(row, rows, self.fields.len());
+        Ok(())
+    }
+
+    /// Checks that the values fit the columns of this table.
+    fn check_row(&self, values: &[DataValue]) -> Result<()> {
         if values.len() != self.fields.len() {
-            Err(BcsvError::InvaildRowLength {
+            return Err(BcsvError::InvaildRowLength {
                 expected: self.fields.len(),
                 actual: values.len(),
-            })
-        } else {
-            for (column, (defintion, value)) in self.fields.iter().zip(values.iter()).enumerate() {
-                if defintion.ty != value.ty() {
-                    return Err(BcsvError::InvaildRowType {
-                        column,
-                        expected: defintion.ty,
-                        actual: value.ty(),
-                    });
-                }
+            });
+        }
+        for (column, (defintion, value)) in self.fields.iter().zip(values.iter()).enumerate() {
+            if defintion.ty != value.ty() {
+                return Err(BcsvError::InvaildRowType {
+                    column,
+                    expected: defintion.ty,
+                    actual: value.ty(),
+                });
             }
-            self.table.append(values);
-            Ok(())
         }
+        Ok(())
+    }
+
+    /// Appends a row of values, which have to match the column types already.
+    pub(crate) fn push_row_unchecked(&mut self, mut values: Vec<DataValue>) {
+        self.table.append(&mut values);
     }
 
     /// Pushes a new column onto the table.
@@ -154,7 +501,8 @@ impl Table {
             );
         }
 
-        let offset = self.fields.len() as u16;
+        let offset = self.next_offset()?;
+        self.original.clear_rows();
         self.fields.push(ColumnDefinition {
             name,
             bitmask: u32::MAX,
@@ -197,7 +545,8 @@ impl Table {
                 .insert(row * self.fields.len() + row + self.fields.len(), data);
         }
 
-        let offset = self.fields.len() as u16;
+        let offset = self.next_offset()?;
+        self.original.clear_rows();
         self.fields.push(ColumnDefinition {
             name,
             bitmask: u32::MAX,
@@ -209,9 +558,27 @@ impl Table {
         Ok(())
     }
 
+    /// Gets the size of a row in bytes, which is where the column that ends last ends.
+    /// Columns can share storage through their bitmask and shift, so this is not the sum of their sizes.
+    pub fn row_size(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|x| x.offset as usize + x.ty.size())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Gets the offset a new column is placed at, right after every existing column.
+    fn next_offset(&self) -> Result<u16> {
+        u16::try_from(self.row_size()).map_err(|_| BcsvError::TooManyColumns)
+    }
+
     /// Gets the number of rows in this table.
     pub fn row_count(&self) -> usize {
-        self.table.len() / self.fields.len()
+        self.table
+            .len()
+            .checked_div(self.fields.len())
+            .unwrap_or_default()
     }
 
     /// Gets the number of columns in this table.
@@ -229,6 +596,64 @@ impl Table {
         self.table.get_mut(row * self.fields.len() + column)
     }
 
+    /// Finds the column with the given name.
+    /// The name is hashed with [`jgadget_hash`](`crate::jgadget_hash`) first and [`old_hash`](`crate::old_hash`) second,
+    /// names like `0x1A2B3C4D` are taken as the hash itself.
+    pub fn column_by_name(&self, name: &str) -> Option<usize> {
+        column_by_name(&self.fields, name)
+    }
+
+    /// Guesses which hash function the column names of this table were made with,
+    /// by counting how many of them the dictionary knows with each one.
+    /// [`NameHash::Jgadget`] wins a tie.
+    pub fn detect_name_hash(&self, dictionary: &HashDictionary) -> NameHash {
+        let known = |function| {
+            self.fields
+                .iter()
+                .filter(|x| dictionary.name(x.name, function).is_some())
+                .count()
+        };
+        if known(NameHash::Old) > known(NameHash::Jgadget) {
+            NameHash::Old
+        } else {
+            NameHash::Jgadget
+        }
+    }
+
+    /// Finds the column with the given name, hashing it with the function from [`Table::detect_name_hash`] first.
+    /// This finds the right column even if the name has the same hash as another name with the other function,
+    /// otherwise it works like [`Table::column_by_name`].
+    pub fn column_by_name_in(&self, name: &str, dictionary: &HashDictionary) -> Option<usize> {
+        let hash = self.detect_name_hash(dictionary).hash(name);
+        self.fields
+            .iter()
+            .position(|x| x.name == hash)
+            .or_else(|| self.column_by_name(name))
+    }
+
+    /// Gets the name of a column from the dictionary, using the hash function from [`Table::detect_name_hash`] first.
+    pub fn column_name<'a>(
+        &self,
+        column: usize,
+        dictionary: &'a HashDictionary,
+    ) -> Option<&'a str> {
+        let hash = self.fields.get(column)?.name;
+        dictionary
+            .name(hash, self.detect_name_hash(dictionary))
+            .or_else(|| dictionary.get(hash))
+    }
+
+    /// Gets a referance to the value in the column with the given name, see [`Table::column_by_name`].
+    pub fn get_by_name(&self, row: usize, name: &str) -> Option<&DataValue> {
+        self.get(row, self.column_by_name(name)?)
+    }
+
+    /// Gets a mutable referance to the value in the column with the given name, see [`Table::column_by_name`].
+    pub fn get_by_name_mut(&mut self, row: usize, name: &str) -> Option<&mut DataValue> {
+        let column = self.column_by_name(name)?;
+        self.get_mut(row, column)
+    }
+
     /// Gets a row from this table as a slice of values.
     pub fn row(&self, row: usize) -> Option<&[DataValue]> {
         let start = row * self.fields.len();
@@ -278,15 +703,109 @@ impl Table {
 
     /// Removes a row from this table, returning it's values in a vec.
     pub fn remove_row(&mut self, row: usize) -> Option<Vec<DataValue>> {
-        if row > self.row_count() {
+        if row >= self.row_count() {
             None
         } else {
             let start = row * self.fields.len();
             let end = start + self.fields.len();
+            self.original.remove_row(row, self.fields.len());
             Some(self.table.drain(start..end).collect())
         }
     }
 
+    /// Swaps two rows of this table.
+    /// Fails if either row doesn't exist.
+    pub fn swap_rows(&mut self, a: usize, b: usize) -> Result<()> {
+        let rows = self.row_count();
+        if let Some(row) = [a, b].into_iter().find(|x| *x >= rows) {
+            return Err(BcsvError::RowOutOfBounds { row, rows });
+        }
+
+        let columns = self.fields.len();
+        for column in 0..columns {
+            self.table.swap(a * columns + column, b * columns + column);
+        }
+        self.original.swap_rows(a, b, rows, columns);
+        Ok(())
+    }
+
+    /// Keeps only the rows the predicate returns true for, in their current order.
+    pub fn retain(&mut self, mut f: impl FnMut(&[DataValue]) -> bool) {
+        let order = (0..self.row_count())
+            .filter(|index| f(&self[*index]))
+            .collect::<Vec<_>>();
+        self.reorder_rows(&order);
+    }
+
+    /// Sorts the rows of this table with the given comparison function.
+    /// The sort is stable, so rows that compare equal keep their order.
+    pub fn sort_rows_by(
+        &mut self,
+        mut compare: impl FnMut(&[DataValue], &[DataValue]) -> Ordering,
+    ) {
+        let mut order = (0..self.row_count()).collect::<Vec<_>>();
+        order.sort_by(|a, b| compare(&self[*a], &self[*b]));
+        self.reorder_rows(&order);
+    }
+
+    /// Sorts the rows of this table by the values of a column, see [`DataValue::total_cmp`].
+    /// The sort is stable, so rows with the same value keep their order.
+    /// Fails if the column doesn't exist.
+    pub fn sort_by_column(&mut self, column: usize) -> Result<()> {
+        let columns = self.fields.len();
+        if column >= columns {
+            return Err(BcsvError::ColumnIndexOutOfBounds { column, columns });
+        }
+        self.sort_rows_by(|a, b| a[column].total_cmp(&b[column]));
+        Ok(())
+    }
+
+    /// Moves the rows into the given order, rows that aren't part of it are removed.
+    fn reorder_rows(&mut self, order: &[usize]) {
+        let rows = self.row_count();
+        let columns = self.fields.len();
+
+        let mut table = Vec::with_capacity(order.len() * columns);
+        for row in order {
+            let start = row * columns;
+            table.extend(
+                self.table[start..start + columns]
+                    .iter_mut()
+                    .map(std::mem::take),
+            );
+        }
+        self.table = table;
+        self.original.reorder_rows(order, rows, columns);
+    }
+
+    /// Creates an iterator over the rows the predicate returns true for.
+    pub fn filter_rows<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&[DataValue]) -> bool + 'a,
+    ) -> impl Iterator<Item = &'a [DataValue]> + 'a {
+        self.iter().filter(move |row| predicate(row))
+    }
+
+    /// Finds the first row with the given value in a column.
+    pub fn find_row(&self, column: usize, value: &DataValue) -> Option<usize> {
+        if column >= self.fields.len() {
+            return None;
+        }
+        self.iter().position(|row| &row[column] == value)
+    }
+
+    /// Finds the first row with the given value in the column with the given name, see [`Table::column_by_name`].
+    /// This is useful to look up objects by an id like `l_id`.
+    pub fn find_row_by_name(&self, name: &str, value: &DataValue) -> Option<usize> {
+        self.find_row(self.column_by_name(name)?, value)
+    }
+
+    /// Removes every row from this table, keeping its columns.
+    pub fn clear_rows(&mut self) {
+        self.table.clear();
+        self.original.clear_rows();
+    }
+
     /// Removes a column from this table, returning it's values in a vec.
     pub fn remove_column(&mut self, column: usize) -> Option<(ColumnDefinition, Vec<DataValue>)> {
         if column > self.fields.len() {
@@ -299,6 +818,7 @@ impl Table {
                 result.push(self.table.remove(index));
             }
 
+            self.original.clear_rows();
             Some((self.fields.remove(column), result))
         }
     }
@@ -326,6 +846,23 @@ impl Table {
     }
 }
 
+pub(crate) fn column_by_name(fields: &[ColumnDefinition], name: &str) -> Option<usize> {
+    // Columns with unknown names can still be found by their hash.
+    let hex = name.strip_prefix("0x").or_else(|| name.strip_prefix("0X"));
+    if let Some(hash) = hex.and_then(|x| u32::from_str_radix(x, 16).ok()) {
+        if let Some(column) = fields.iter().position(|x| x.name == hash) {
+            return Some(column);
+        }
+    }
+
+    [NameHash::Jgadget, NameHash::Old]
+        .iter()
+        .find_map(|function| {
+            let hash = function.hash(name);
+            fields.iter().position(|x| x.name == hash)
+        })
+}
+
 impl Default for Table {
     fn default() -> Self {
         Table::new()
@@ -445,7 +982,8 @@ impl<'a> Iterator for RowIter<'a> {
     type Item = &'a [DataValue];
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.table.len() < self.fields {
+        // Without any columns, a table has no rows.
+        if self.fields == 0 || self.table.len() < self.fields {
             return None;
         }
 
@@ -456,7 +994,11 @@ impl<'a> Iterator for RowIter<'a> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let size = self.table.len() / self.fields;
+        let size = self
+            .table
+            .len()
+            .checked_div(self.fields)
+            .unwrap_or_default();
         (size, Some(size))
     }
 }
@@ -474,7 +1016,8 @@ impl<'a> Iterator for RowIterMut<'a> {
     type Item = &'a mut [DataValue];
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.table.len() < self.fields {
+        // Without any columns, a table has no rows.
+        if self.fields == 0 || self.table.len() < self.fields {
             return None;
         }
 
@@ -486,7 +1029,11 @@ impl<'a> Iterator for RowIterMut<'a> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let size = self.table.len() / self.fields;
+        let size = self
+            .table
+            .len()
+            .checked_div(self.fields)
+            .unwrap_or_default();
         (size, Some(size))
     }
 }
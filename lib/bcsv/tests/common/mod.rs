@@ -0,0 +1,81 @@
+//! Helpers shared by the integration tests, which build BCSV files byte by byte.
+#![allow(dead_code)]
+
+use bcsv::byteorder::ByteOrder;
+use bcsv::{BcsvError, Table, WriteOptions};
+use std::io::Cursor;
+
+/// The raw parts of a synthetic BCSV file.
+pub struct Fixture {
+    /// Name, bitmask, offset, shift and type of every column.
+    pub columns: Vec<(u32, u32, u16, u8, u8)>,
+    /// Bytes between the column definitions and the rows.
+    pub gap: Vec<u8>,
+    pub row_size: u32,
+    pub rows: Vec<Vec<u8>>,
+    pub pool: Vec<u8>,
+    pub trailer: Vec<u8>,
+}
+
+impl Fixture {
+    pub fn build<T: ByteOrder>(&self) -> Vec<u8> {
+        let data_offset = 0x10 + self.columns.len() * 0x0C + self.gap.len();
+        let mut result = words::<T>(&[
+            self.rows.len() as u32,
+            self.columns.len() as u32,
+            data_offset as u32,
+            self.row_size,
+        ]);
+        for (name, bitmask, offset, shift, ty) in &self.columns {
+            result.extend(words::<T>(&[*name, *bitmask]));
+            let mut offset_bytes = [0; 2];
+            T::write_u16(&mut offset_bytes, *offset);
+            result.extend(offset_bytes);
+            result.extend([*shift, *ty]);
+        }
+        result.extend(&self.gap);
+        for row in &self.rows {
+            assert_eq!(row.len(), self.row_size as usize);
+            result.extend(row);
+        }
+        result.extend(&self.pool);
+        result.extend(&self.trailer);
+        result
+    }
+}
+
+pub fn words<T: ByteOrder>(values: &[u32]) -> Vec<u8> {
+    let mut result = vec![0; values.len() * 4];
+    T::write_u32_into(values, &mut result);
+    result
+}
+
+pub fn padding(size: usize, byte: u8) -> Vec<u8> {
+    vec![byte; ((size + 31) & !31) - size]
+}
+
+pub fn read<T: ByteOrder>(data: &[u8]) -> Table {
+    Table::read::<T>(Cursor::new(data)).unwrap()
+}
+
+pub fn try_write<T: ByteOrder>(table: &Table, options: WriteOptions) -> Result<Vec<u8>, BcsvError> {
+    let mut result = Vec::new();
+    table.write_with::<T>(&mut result, options)?;
+    Ok(result)
+}
+
+pub fn write<T: ByteOrder>(table: &Table, options: WriteOptions) -> Vec<u8> {
+    try_write::<T>(table, options).unwrap()
+}
+
+pub fn lossless() -> WriteOptions {
+    WriteOptions {
+        lossless: true,
+        ..Default::default()
+    }
+}
+
+/// Every value of the table in row order, as text.
+pub fn values(table: &Table) -> Vec<String> {
+    table.iter().flatten().map(|x| x.to_string()).collect()
+}
@@ -0,0 +1,62 @@
+use bcsv::{crack_all, jgadget_hash, old_hash, Collision, CrackOptions, NameHash};
+use std::collections::HashSet;
+
+fn words(words: &[&str]) -> Vec<String> {
+    words.iter().map(|x| x.to_string()).collect()
+}
+
+#[test]
+fn prefixes_words_and_suffixes() {
+    let targets = HashSet::from([jgadget_hash(b"l_ObjArg3"), old_hash(b"Obj_name")]);
+    let options = CrackOptions {
+        words: words(&["Obj", "Arg", "name"]),
+        prefixes: words(&["l_", "Obj_"]),
+        max_number: Some(9),
+        threads: 2,
+        ..Default::default()
+    };
+
+    let collisions = crack_all(&targets, &options);
+    assert!(collisions.contains(&Collision {
+        hash: jgadget_hash(b"l_ObjArg3"),
+        function: NameHash::Jgadget,
+        name: "l_ObjArg3".into(),
+    }));
+    assert!(collisions.contains(&Collision {
+        hash: old_hash(b"Obj_name"),
+        function: NameHash::Old,
+        name: "Obj_name".into(),
+    }));
+    assert!(collisions.iter().all(|x| targets.contains(&x.hash)));
+}
+
+#[test]
+fn brute_force() {
+    let targets = HashSet::from([jgadget_hash(b"l_id"), old_hash(b"Zq")]);
+    let mut options = CrackOptions {
+        prefixes: words(&["l_"]),
+        brute_force_length: 2,
+        threads: 1,
+        ..Default::default()
+    };
+
+    let single = crack_all(&targets, &options);
+    assert!(single
+        .iter()
+        .any(|x| x.name == "l_id" && x.function == NameHash::Jgadget));
+    assert!(single
+        .iter()
+        .any(|x| x.name == "Zq" && x.function == NameHash::Old));
+
+    options.threads = 4;
+    assert_eq!(crack_all(&targets, &options), single);
+}
+
+#[test]
+fn no_targets() {
+    let options = CrackOptions {
+        words: words(&["name"]),
+        ..Default::default()
+    };
+    assert!(crack_all(&HashSet::new(), &options).is_empty());
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "derive")]
+
+use bcsv::byteorder::BigEndian;
+use bcsv::{BcsvError, BcsvRow, DataType, DataValue, Table};
+use std::io::Cursor;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, PartialEq, BcsvRow)]
+struct Object {
+    name: String,
+    l_id: i32,
+    SW_APPEAR: u32,
+    pos_x: f32,
+    CommonPath_ID: i16,
+    ViewGroupId: u8,
+    #[bcsv(ty = Int32)]
+    ShapeModelNo: u16,
+    #[bcsv(name = "Flags", offset = 24, bitmask = 0x0F)]
+    low: u8,
+    #[bcsv(name = "Flags2", offset = 24, bitmask = 0xF0, shift = 4)]
+    high: u8,
+    Disabled: bool,
+}
+
+fn objects() -> Vec<Object> {
+    vec![
+        Object {
+            name: "Kuribo".into(),
+            l_id: 0,
+            SW_APPEAR: u32::MAX,
+            pos_x: -12.5,
+            CommonPath_ID: -1,
+            ViewGroupId: 200,
+            ShapeModelNo: 60000,
+            low: 3,
+            high: 5,
+            Disabled: false,
+        },
+        Object {
+            name: "スーパーキノコ".into(),
+            l_id: 7,
+            SW_APPEAR: 12,
+            pos_x: 1250.0,
+            CommonPath_ID: 4,
+            ViewGroupId: 0,
+            ShapeModelNo: 0,
+            low: 15,
+            high: 0,
+            Disabled: true,
+        },
+    ]
+}
+
+#[test]
+fn round_trip() {
+    let table = Table::from_rows(&objects()).unwrap();
+    let mut data = Cursor::new(Vec::new());
+    table.write::<BigEndian>(&mut data).unwrap();
+
+    data.set_position(0);
+    let table = Table::read::<BigEndian>(&mut data).unwrap();
+    assert_eq!(table.rows::<Object>().unwrap(), objects());
+}
+
+#[test]
+fn lossy_conversions_are_errors() {
+    #[derive(Debug, BcsvRow)]
+    struct Small {
+        #[bcsv(ty = Int32)]
+        value: u8,
+    }
+
+    #[derive(Debug, BcsvRow)]
+    struct Wide {
+        value: i32,
+    }
+
+    let table = Table::from_rows(&[Wide { value: 255 }, Wide { value: 300 }]).unwrap();
+    match table.rows::<Small>() {
+        Err(BcsvError::InvalidConversion { value, target }) => {
+            assert_eq!(value, "300");
+            assert_eq!(target, "u8");
+        }
+        x => panic!("expected a conversion error, got {x:?}"),
+    }
+
+    assert!(matches!(
+        Table::from_rows(&[Small { value: 255 }]),
+        Ok(x) if x.row(0).unwrap() == [DataValue::Int32(255)]
+    ));
+}
+
+#[test]
+fn unparsable_strings_are_errors() {
+    #[derive(Debug, BcsvRow)]
+    struct Text {
+        #[bcsv(ty = OffsetString)]
+        value: String,
+    }
+
+    #[derive(Debug, BcsvRow)]
+    struct Number {
+        #[bcsv(ty = Int32)]
+        value: String,
+    }
+
+    let table = Table::from_rows(&[Text { value: "12".into() }]).unwrap();
+    assert!(table.row(0).unwrap() == [DataValue::OffsetString("12".into())]);
+    assert!(matches!(
+        Table::from_rows(&[Number { value: "twelve".into() }]),
+        Err(BcsvError::InvalidConversion { target, .. }) if target == DataType::Int32.to_string()
+    ));
+}
+
+#[test]
+fn invalid_attributes() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
+
+#[test]
+fn rows_of_the_wrong_length_are_errors() {
+    #[derive(Debug, BcsvRow)]
+    struct Pair {
+        first: i32,
+        second: i32,
+    }
+
+    for row in [
+        vec![DataValue::Int32(1)],
+        vec![DataValue::Int32(1); 3],
+        Vec::new(),
+    ] {
+        assert!(matches!(
+            Pair::from_row(&row),
+            Err(BcsvError::InvaildRowLength { expected: 2, actual }) if actual == row.len()
+        ));
+    }
+    let pair = Pair::from_row(&[DataValue::Int32(1), DataValue::Int32(2)]).unwrap();
+    assert_eq!((pair.first, pair.second), (1, 2));
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use bcsv::byteorder::BigEndian;
+use bcsv::{BcsvError, DataType, DataValue, Encoding, ReadOptions, Table, WriteOptions};
+use common::{read, try_write, words, Fixture};
+use std::io::Cursor;
+
+fn table(rows: &[(&str, &str)]) -> Table {
+    let mut table = Table::new();
+    for (name, ty) in [
+        (1, DataType::Int32),
+        (2, DataType::OffsetString),
+        (3, DataType::InlineString),
+    ] {
+        table.push_column(name, ty, &ty.default_value()).unwrap();
+    }
+    for (offset, inline) in rows {
+        table
+            .push_row(&mut vec![
+                DataValue::Int32(0),
+                DataValue::OffsetString(offset.to_string()),
+                DataValue::InlineString(inline.to_string()),
+            ])
+            .unwrap();
+    }
+    table
+}
+
+fn write(table: &Table, encoding: Encoding, strict: bool) -> Result<Vec<u8>, BcsvError> {
+    let options = WriteOptions {
+        encoding,
+        strict,
+        ..Default::default()
+    };
+    try_write::<BigEndian>(table, options)
+}
+
+#[test]
+fn strict_writes_report_the_cell() {
+    let rows = table(&[
+        ("Kuribo", "a"),
+        ("キノコ", "b"),
+        ("Kuribo", "c"),
+        ("ok", "\u{1F344}"),
+    ]);
+    match write(&rows, Encoding::ShiftJis, true) {
+        Err(BcsvError::UnmappableText {
+            row,
+            column,
+            encoding,
+        }) => assert_eq!((row, column, encoding), (3, 2, Encoding::ShiftJis)),
+        x => panic!("expected unmappable text, got {:?}", x.map(|_| ())),
+    }
+
+    let rows = table(&[("Kuribo", "a"), ("é\u{1F344}", "b")]);
+    match write(&rows, Encoding::Windows1252, true) {
+        Err(BcsvError::UnmappableText { row, column, .. }) => assert_eq!((row, column), (1, 1)),
+        x => panic!("expected unmappable text, got {:?}", x.map(|_| ())),
+    }
+
+    assert!(write(&rows, Encoding::Windows1252, false).is_ok());
+}
+
+#[test]
+fn strict_reads_report_the_cell() {
+    let data = Fixture {
+        columns: vec![(1, 0xFFFF_FFFF, 0, 0, 0), (2, 0xFFFF_FFFF, 4, 0, 6)],
+        gap: Vec::new(),
+        row_size: 8,
+        rows: vec![words::<BigEndian>(&[0, 0]), words::<BigEndian>(&[1, 3])],
+        pool: b"ok\0\xFF\xFE\0".to_vec(),
+        trailer: Vec::new(),
+    }
+    .build::<BigEndian>();
+
+    let read = |strict| {
+        let options = ReadOptions {
+            encoding: Encoding::Utf8,
+            strict,
+        };
+        Table::read_with::<BigEndian>(Cursor::new(&data), options)
+    };
+    match read(true) {
+        Err(BcsvError::UnmappableText {
+            row,
+            column,
+            encoding,
+        }) => assert_eq!((row, column, encoding), (1, 1, Encoding::Utf8)),
+        x => panic!("expected unmappable text, got {:?}", x.map(|_| ())),
+    }
+
+    let table = read(false).unwrap();
+    assert!(table[(1, 1)] == DataValue::OffsetString("\u{FFFD}\u{FFFD}".into()));
+}
+
+#[test]
+fn round_trip_in_every_encoding() {
+    let rows = table(&[("Kuribo", "Cabaña"), ("Señor", "naïve")]);
+    for encoding in [Encoding::Utf8, Encoding::Windows1252] {
+        let written = write(&rows, encoding, true).unwrap();
+        let options = ReadOptions {
+            encoding,
+            strict: true,
+        };
+        let table = Table::read_with::<BigEndian>(Cursor::new(&written), options).unwrap();
+        let values = table
+            .iter()
+            .flatten()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["0", "Kuribo", "Cabaña", "0", "Señor", "naïve"]);
+    }
+
+    let written = write(&table(&[("キノコ", "スター")]), Encoding::ShiftJis, true).unwrap();
+    let table = read::<BigEndian>(&written);
+    assert!(table[(0, 1)] == DataValue::OffsetString("キノコ".into()));
+    assert!(table[(0, 2)] == DataValue::InlineString("スター".into()));
+}
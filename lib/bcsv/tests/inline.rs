@@ -0,0 +1,132 @@
+mod common;
+
+use bcsv::byteorder::BigEndian;
+use bcsv::{BcsvError, DataType, DataValue, Table};
+use common::{padding, read, try_write, words, Fixture};
+
+fn table(text: &str) -> Table {
+    let mut table = Table::new();
+    table
+        .push_column(
+            0x1234,
+            DataType::InlineString,
+            &DataValue::InlineString(String::new()),
+        )
+        .unwrap();
+    table
+        .push_row(&mut vec![DataValue::InlineString(text.into())])
+        .unwrap();
+    table
+}
+
+fn write(table: &Table) -> Result<Vec<u8>, BcsvError> {
+    try_write::<BigEndian>(table, Default::default())
+}
+
+fn slot(data: &[u8]) -> &[u8] {
+    let start = 0x10 + 0x0C;
+    &data[start..start + 32]
+}
+
+#[test]
+fn strings_fill_up_to_31_bytes() {
+    let text = "a".repeat(31);
+    let written = write(&table(&text)).unwrap();
+    assert_eq!(&slot(&written)[..31], text.as_bytes());
+    assert_eq!(slot(&written)[31], 0);
+
+    let reread = read::<BigEndian>(&written);
+    assert!(reread[(0, 0)] == DataValue::InlineString(text));
+}
+
+#[test]
+fn strings_need_room_for_the_terminator() {
+    assert!(matches!(
+        write(&table(&"a".repeat(32))),
+        Err(BcsvError::InlineStringTooLong {
+            row: 0,
+            column: 0,
+            length: 32
+        })
+    ));
+}
+
+#[test]
+fn too_long_strings_report_the_cell() {
+    let mut table = table("short");
+    table
+        .push_column(
+            0x5678,
+            DataType::InlineString,
+            &DataValue::InlineString("fits".into()),
+        )
+        .unwrap();
+    table
+        .push_row(&mut vec![
+            DataValue::InlineString("short".into()),
+            DataValue::InlineString("b".repeat(40)),
+        ])
+        .unwrap();
+    assert!(matches!(
+        write(&table),
+        Err(BcsvError::InlineStringTooLong {
+            row: 1,
+            column: 1,
+            length: 40
+        })
+    ));
+}
+
+#[test]
+fn length_is_counted_in_encoded_bytes() {
+    // Every character takes two bytes in Shift-JIS.
+    assert!(write(&table(&"あ".repeat(15))).is_ok());
+    assert!(matches!(
+        write(&table(&"あ".repeat(16))),
+        Err(BcsvError::InlineStringTooLong { length: 32, .. })
+    ));
+}
+
+#[test]
+fn short_strings_are_padded_with_nul() {
+    let written = write(&table("Kuribo")).unwrap();
+    assert_eq!(&slot(&written)[..6], b"Kuribo");
+    assert!(slot(&written)[6..].iter().all(|x| *x == 0));
+}
+
+#[test]
+fn reading_stops_at_the_first_nul() {
+    let slot = |text: &[u8]| {
+        let mut slot = text.to_vec();
+        slot.resize(32, 0);
+        slot
+    };
+    let mut garbage = slot(b"name\0");
+    garbage[8..12].copy_from_slice(b"junk");
+    let mut full = slot(b"");
+    full.fill(b'x');
+
+    let mut fixture = Fixture {
+        columns: vec![
+            (0x1234, 0xFFFF_FFFF, 0, 0, 1),
+            (0x5678, 0xFFFF_FFFF, 32, 0, 0),
+        ],
+        gap: Vec::new(),
+        row_size: 36,
+        rows: [slot(b"name"), garbage, slot(b""), full]
+            .into_iter()
+            .map(|mut x| {
+                x.extend(words::<BigEndian>(&[7]));
+                x
+            })
+            .collect(),
+        pool: Vec::new(),
+        trailer: Vec::new(),
+    };
+    fixture.trailer = padding(fixture.build::<BigEndian>().len(), b'@');
+    let table = read::<BigEndian>(&fixture.build::<BigEndian>());
+
+    let values = table.iter().map(|x| x[0].to_string()).collect::<Vec<_>>();
+    assert_eq!(values, ["name", "name", "", "x".repeat(32).as_str()]);
+    assert!(table.iter().all(|x| x[1] == DataValue::Int32(7)));
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use bcsv::byteorder::{BigEndian, ByteOrder};
+use bcsv::{BcsvError, DataType, DataValue, Table, TableReader};
+use common::{words, Fixture};
+use std::io::Cursor;
+
+fn read(data: &[u8]) -> Result<Table, BcsvError> {
+    Table::read::<BigEndian>(Cursor::new(data))
+}
+
+/// A table with a single column of the given type, shift and row.
+fn single(ty: u8, shift: u8, row: Vec<u8>, pool: &[u8]) -> Vec<u8> {
+    Fixture {
+        columns: vec![(0x1234, 0xFFFF_FFFF, 0, shift, ty)],
+        gap: Vec::new(),
+        row_size: row.len() as u32,
+        rows: vec![row],
+        pool: pool.to_vec(),
+        trailer: Vec::new(),
+    }
+    .build::<BigEndian>()
+}
+
+#[test]
+fn huge_row_count() {
+    let mut data = single(3, 0, words::<BigEndian>(&[1]), b"");
+    BigEndian::write_u32(&mut data[0x00..], u32::MAX);
+    BigEndian::write_u32(&mut data[0x0C..], u32::MAX);
+    assert!(matches!(
+        read(&data),
+        Err(BcsvError::HeaderOutOfBounds { field: "rows", .. })
+    ));
+    assert!(TableReader::read::<BigEndian>(Cursor::new(&data)).is_err());
+}
+
+#[test]
+fn huge_row_count_of_empty_rows() {
+    let mut data = Fixture {
+        columns: vec![(0x1234, 0xFFFF_FFFF, 0, 0, 3)],
+        gap: Vec::new(),
+        row_size: 0,
+        rows: Vec::new(),
+        pool: Vec::new(),
+        trailer: Vec::new(),
+    }
+    .build::<BigEndian>();
+    assert_eq!(data.len(), 28);
+    BigEndian::write_u32(&mut data[0x00..], u32::MAX);
+    assert!(matches!(read(&data), Err(BcsvError::EmptyRows(u32::MAX))));
+    assert!(TableReader::read::<BigEndian>(Cursor::new(&data)).is_err());
+
+    // Without any rows, a row size of 0 is fine.
+    BigEndian::write_u32(&mut data[0x00..], 0);
+    assert_eq!(read(&data).unwrap().row_count(), 0);
+}
+
+#[test]
+fn huge_column_count() {
+    let mut data = single(3, 0, words::<BigEndian>(&[1]), b"");
+    BigEndian::write_u32(&mut data[0x04..], u32::MAX);
+    assert!(matches!(
+        read(&data),
+        Err(BcsvError::HeaderOutOfBounds {
+            field: "columns",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn truncated_rows() {
+    let data = single(3, 0, words::<BigEndian>(&[1, 2]), b"");
+    assert!(read(&data[..data.len() - 1]).is_err());
+}
+
+#[test]
+fn shift_past_the_type_width() {
+    for (ty, size, bits) in [(0, 4, 32), (3, 4, 32), (4, 2, 16), (5, 1, 8)] {
+        let row = vec![0xFF; size];
+        assert!(read(&single(ty, bits - 1, row.clone(), b"")).is_ok());
+        assert!(matches!(
+            read(&single(ty, bits, row.clone(), b"")),
+            Err(BcsvError::InvalidShift { name: 0x1234, .. })
+        ));
+        assert!(matches!(
+            read(&single(ty, 40, row, b"")),
+            Err(BcsvError::InvalidShift { shift: 40, .. })
+        ));
+    }
+
+    // Types that aren't integers ignore the shift.
+    let table = read(&single(2, 40, words::<BigEndian>(&[0]), b"")).unwrap();
+    assert_eq!(table.definitions()[0].ty, DataType::Float);
+}
+
+#[test]
+fn offset_string_out_of_bounds() {
+    let data = single(6, 0, words::<BigEndian>(&[0x100]), b"text\0");
+    assert!(matches!(
+        read(&data),
+        Err(BcsvError::StringOutOfBounds {
+            row: 0,
+            column: 0,
+            offset: 0x100,
+        })
+    ));
+
+    let reader = TableReader::read::<BigEndian>(Cursor::new(&data)).unwrap();
+    let row = reader.row(0).unwrap();
+    assert!(matches!(
+        row.get(0),
+        Some(Err(BcsvError::StringOutOfBounds { .. }))
+    ));
+
+    // An offset right at the end of the pool is an empty string.
+    let data = single(6, 0, words::<BigEndian>(&[5]), b"text\0");
+    assert!(read(&data).unwrap().get(0, 0) == Some(&DataValue::OffsetString(String::new())));
+}
@@ -0,0 +1,127 @@
+use bcsv::{jgadget_hash, old_hash, DataType, HashDictionary, NameHash, Table};
+
+fn columns(names: &[u32]) -> Table {
+    let mut table = Table::new();
+    for name in names {
+        table
+            .push_column(*name, DataType::Int32, &DataType::Int32.default_value())
+            .unwrap();
+    }
+    table
+}
+
+#[test]
+fn hex_names_are_hashed_like_any_other_name() {
+    assert_eq!(NameHash::Jgadget.hash("0x10"), jgadget_hash(b"0x10"));
+    assert_eq!(NameHash::Old.hash("0x10"), old_hash(b"0x10"));
+}
+
+#[test]
+fn column_by_hex_hash() {
+    let table = columns(&[jgadget_hash(b"name"), 0x1A2B_3C4D]);
+    assert_eq!(table.column_by_name("0x1A2B3C4D"), Some(1));
+    assert_eq!(table.column_by_name("0X1a2b3c4d"), Some(1));
+    assert_eq!(table.column_by_name("0xDEADBEEF"), None);
+    assert_eq!(table.column_by_name("name"), Some(0));
+}
+
+#[test]
+fn hex_names_fall_back_to_their_hash() {
+    let table = columns(&[jgadget_hash(b"0x10")]);
+    assert_eq!(table.column_by_name("0x10"), Some(0));
+}
+
+#[test]
+fn column_by_name_tries_jgadget_before_old() {
+    let table = columns(&[old_hash(b"l_id"), jgadget_hash(b"l_id")]);
+    assert_eq!(table.column_by_name("l_id"), Some(1));
+
+    let table = columns(&[old_hash(b"l_id")]);
+    assert_eq!(table.column_by_name("l_id"), Some(0));
+}
+
+#[test]
+fn first_name_wins() {
+    // "Aa" and "BB" have the same jgadget hash.
+    assert_eq!(jgadget_hash(b"Aa"), jgadget_hash(b"BB"));
+
+    let dictionary: HashDictionary = ["Aa", "BB"].into_iter().collect();
+    assert_eq!(
+        dictionary.name(jgadget_hash(b"Aa"), NameHash::Jgadget),
+        Some("Aa")
+    );
+    assert_eq!(dictionary.name(old_hash(b"BB"), NameHash::Old), Some("BB"));
+
+    // The name that lost the lookup is still kept.
+    assert_eq!(dictionary.len(), 2);
+    assert_eq!(dictionary.names().collect::<Vec<_>>(), ["Aa", "BB"]);
+}
+
+#[test]
+fn merge_keeps_existing_names() {
+    let mut dictionary: HashDictionary = ["Aa", "l_id"].into_iter().collect();
+    dictionary.merge(["BB", "name"].into_iter().collect());
+
+    assert_eq!(dictionary.get(jgadget_hash(b"BB")), Some("Aa"));
+    assert_eq!(dictionary.name(old_hash(b"BB"), NameHash::Old), Some("BB"));
+    assert_eq!(dictionary.get(jgadget_hash(b"name")), Some("name"));
+    assert_eq!(dictionary.get(old_hash(b"l_id")), Some("l_id"));
+    assert_eq!(
+        dictionary.names().collect::<Vec<_>>(),
+        ["Aa", "BB", "l_id", "name"]
+    );
+}
+
+#[test]
+fn names_are_counted_once() {
+    let mut dictionary: HashDictionary = ["l_id", "name", "l_id"].into_iter().collect();
+    dictionary.merge(["name"].into_iter().collect());
+    assert_eq!(dictionary.len(), 2);
+    assert!(!dictionary.is_empty());
+    assert!(HashDictionary::new().is_empty());
+}
+
+#[test]
+fn get_tries_jgadget_before_old() {
+    // The old hash of "|@" is the jgadget hash of " !!".
+    assert_eq!(old_hash(b"|@"), jgadget_hash(b" !!"));
+
+    let dictionary: HashDictionary = ["|@", " !!"].into_iter().collect();
+    assert_eq!(dictionary.get(old_hash(b"|@")), Some(" !!"));
+    assert_eq!(dictionary.name(old_hash(b"|@"), NameHash::Old), Some("|@"));
+}
+
+#[test]
+fn column_names_from_a_dictionary() {
+    let table = columns(&[jgadget_hash(b"l_id"), jgadget_hash(b"name"), 0x1A2B_3C4D]);
+    let dictionary: HashDictionary = ["l_id", "name"].into_iter().collect();
+    assert_eq!(table.detect_name_hash(&dictionary), NameHash::Jgadget);
+    assert_eq!(table.column_name(0, &dictionary), Some("l_id"));
+    assert_eq!(table.column_name(1, &dictionary), Some("name"));
+    assert_eq!(table.column_name(2, &dictionary), None);
+    assert_eq!(table.column_name(3, &dictionary), None);
+    assert_eq!(table.column_by_name_in("name", &dictionary), Some(1));
+    assert_eq!(table.column_by_name_in("0x1A2B3C4D", &dictionary), Some(2));
+}
+
+#[test]
+fn dictionary_lookup_uses_the_hash_of_the_table() {
+    // The jgadget hash of " !!" is the old hash of "|@".
+    let table = columns(&[old_hash(b"|@"), old_hash(b" !!"), old_hash(b"l_id")]);
+    let dictionary: HashDictionary = ["|@", " !!", "l_id"].into_iter().collect();
+    assert_eq!(table.detect_name_hash(&dictionary), NameHash::Old);
+
+    // Without the dictionary, the jgadget hash of " !!" is found first.
+    assert_eq!(table.column_by_name(" !!"), Some(0));
+    assert_eq!(table.column_by_name_in(" !!", &dictionary), Some(1));
+    assert_eq!(table.column_by_name_in("|@", &dictionary), Some(0));
+
+    assert_eq!(dictionary.get(old_hash(b"|@")), Some(" !!"));
+    assert_eq!(table.column_name(0, &dictionary), Some("|@"));
+    assert_eq!(table.column_name(1, &dictionary), Some(" !!"));
+
+    // Names that aren't found with the detected function fall back to both.
+    let dictionary: HashDictionary = ["|@", "l_id"].into_iter().collect();
+    let table = columns(&[old_hash(b"|@"), old_hash(b"l_id"), jgadget_hash(b"name")]);
+    assert_eq!(table.column_by_name_in("name", &dictionary), Some(2));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use bcsv::byteorder::{BigEndian, ByteOrder};
+use bcsv::{DataValue, StringPool, Table, WriteOptions};
+use common::{read, values, words, Fixture};
+
+const ROWS: usize = 5;
+const DATA_OFFSET: usize = 0x10 + 0x0C;
+
+/// One offset string column, with two rows sharing an offset,
+/// a duplicate string and an offset into the middle of a string.
+fn original() -> Vec<u8> {
+    Fixture {
+        columns: vec![(0x1234, 0xFFFF_FFFF, 0, 0, 6)],
+        gap: Vec::new(),
+        row_size: 4,
+        rows: [0, 0, 1, 4, 8].map(|x| words::<BigEndian>(&[x])).to_vec(),
+        pool: b"abc\0abc\0de\0".to_vec(),
+        trailer: Vec::new(),
+    }
+    .build::<BigEndian>()
+}
+
+fn write(table: &Table, string_pool: StringPool) -> Vec<u8> {
+    let options = WriteOptions {
+        string_pool,
+        ..Default::default()
+    };
+    common::write::<BigEndian>(table, options)
+}
+
+fn offsets(data: &[u8]) -> Vec<u32> {
+    let rows = &data[DATA_OFFSET..DATA_OFFSET + ROWS * 4];
+    rows.chunks(4).map(BigEndian::read_u32).collect()
+}
+
+fn pool(data: &[u8]) -> &[u8] {
+    let pool = &data[DATA_OFFSET + ROWS * 4..];
+    let end = pool.iter().rposition(|x| *x == 0).map_or(0, |x| x + 1);
+    &pool[..end]
+}
+
+#[test]
+fn shared_offsets_are_kept() {
+    let table = read::<BigEndian>(&original());
+    let written = write(&table, StringPool::Preserve);
+    assert_eq!(offsets(&written), [0, 0, 1, 4, 8]);
+    assert_eq!(pool(&written), b"abc\0abc\0de\0");
+}
+
+#[test]
+fn offsets_into_the_middle_of_a_string_are_kept() {
+    let table = read::<BigEndian>(&original());
+    assert_eq!(values(&table), ["abc", "abc", "bc", "abc", "de"]);
+
+    let written = write(&table, StringPool::Preserve);
+    assert_eq!(offsets(&written)[2], 1);
+    let reread = read::<BigEndian>(&written);
+    assert_eq!(values(&reread), values(&table));
+}
+
+#[test]
+fn edited_strings_are_appended() {
+    let mut table = read::<BigEndian>(&original());
+    table[(0, 0)] = DataValue::OffsetString("new".into());
+    table[(2, 0)] = DataValue::OffsetString("newer".into());
+
+    let written = write(&table, StringPool::Preserve);
+    assert_eq!(offsets(&written), [11, 0, 15, 4, 8]);
+    assert_eq!(pool(&written), b"abc\0abc\0de\0new\0newer\0");
+
+    let reread = read::<BigEndian>(&written);
+    assert_eq!(values(&reread), ["new", "abc", "newer", "abc", "de"]);
+}
+
+#[test]
+fn edited_strings_reuse_existing_values() {
+    let mut table = read::<BigEndian>(&original());
+    table[(0, 0)] = DataValue::OffsetString("de".into());
+
+    let written = write(&table, StringPool::Preserve);
+    assert_eq!(offsets(&written), [8, 0, 1, 4, 8]);
+    assert_eq!(pool(&written), b"abc\0abc\0de\0");
+}
+
+#[test]
+fn deduplicated_strings_share_offsets() {
+    let table = read::<BigEndian>(&original());
+    let written = write(&table, StringPool::Deduplicate);
+    assert_eq!(offsets(&written), [0, 0, 4, 0, 7]);
+    assert_eq!(pool(&written), b"abc\0bc\0de\0");
+}
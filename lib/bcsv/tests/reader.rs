@@ -0,0 +1,139 @@
+mod common;
+
+use bcsv::byteorder::{BigEndian, ByteOrder, LittleEndian};
+use bcsv::{DataValue, Endian, Table, TableReader};
+use common::{padding, read, words, write, Fixture};
+use std::io::Cursor;
+
+/// Every column type, two integers packed into one word and strings that share the pool.
+fn every_type<T: ByteOrder>() -> Vec<u8> {
+    let row = |packed: u32,
+               string: u32,
+               float: f32,
+               unsigned: u32,
+               short: i16,
+               byte: i8,
+               inline: &[u8]| {
+        let mut row = words::<T>(&[packed, string]);
+        let mut bytes = [0; 4];
+        T::write_f32(&mut bytes, float);
+        row.extend(bytes);
+        row.extend(words::<T>(&[unsigned]));
+        let mut bytes = [0; 2];
+        T::write_i16(&mut bytes, short);
+        row.extend(bytes);
+        row.push(byte as u8);
+        row.push(0);
+        let mut bytes = [0; 32];
+        bytes[..inline.len()].copy_from_slice(inline);
+        row.extend(bytes);
+        row
+    };
+
+    let mut fixture = Fixture {
+        columns: vec![
+            (0x1111, 0x0000_00FF, 0, 0, 0),
+            (0x2222, 0x0000_FF00, 0, 8, 0),
+            (0x3333, 0xFFFF_FFFF, 4, 0, 6),
+            (0x4444, 0xFFFF_FFFF, 8, 0, 2),
+            (0x5555, 0xFFFF_FFFF, 12, 0, 3),
+            (0x6666, 0xFFFF_FFFF, 16, 0, 4),
+            (0x7777, 0xFFFF_FFFF, 18, 0, 5),
+            (0x8888, 0xFFFF_FFFF, 20, 0, 1),
+        ],
+        gap: Vec::new(),
+        row_size: 52,
+        rows: vec![
+            row(0x0000_0201, 0, 1.5, u32::MAX, -2, -1, b"Kuribo"),
+            row(0x0000_FFFF, 1, -0.25, 7, i16::MAX, i8::MIN, b""),
+            row(0x0000_0000, 4, 1e9, 0, 0, 0, b"\x83L\x83m\x83R"),
+            row(0x0000_0A0B, 0, 0.0, 12, -300, 100, b"name"),
+        ],
+        pool: b"abc\0\x83X\x83^\x81[\0".to_vec(),
+        trailer: Vec::new(),
+    };
+    fixture.trailer = padding(fixture.build::<T>().len(), b'@');
+    fixture.build::<T>()
+}
+
+fn assert_same(reader: &TableReader, table: &Table) {
+    assert_eq!(reader.definitions(), table.definitions());
+    assert_eq!(reader.row_count(), table.row_count());
+    assert_eq!(reader.column_count(), table.column_count());
+    assert_eq!(reader.iter().count(), table.row_count());
+
+    for (index, row) in reader.iter().enumerate() {
+        assert_eq!(row.index(), index);
+        let values = row.to_vec().unwrap();
+        assert!(values == table.row(index).unwrap(), "row {index} differs");
+        for column in 0..table.column_count() {
+            assert!(row.get(column).unwrap().unwrap() == table[(index, column)]);
+        }
+        assert!(row.get(table.column_count()).is_none());
+    }
+    assert!(reader.row(table.row_count()).is_none());
+
+    let converted = reader.to_table().unwrap();
+    assert_eq!(converted.definitions(), table.definitions());
+    assert!(converted.iter().eq(table.iter()));
+}
+
+#[test]
+fn matches_table_big_endian() {
+    let data = every_type::<BigEndian>();
+    let reader = TableReader::read::<BigEndian>(Cursor::new(&data)).unwrap();
+    let table = read::<BigEndian>(&data);
+    assert_same(&reader, &table);
+
+    assert!(table[(1, 2)] == DataValue::OffsetString("bc".into()));
+    assert!(table[(2, 2)] == DataValue::OffsetString("スター".into()));
+    assert!(table[(2, 7)] == DataValue::InlineString("キノコ".into()));
+}
+
+#[test]
+fn matches_table_little_endian() {
+    let data = every_type::<LittleEndian>();
+    let (reader, endian) = TableReader::read_auto(Cursor::new(&data)).unwrap();
+    assert_eq!(endian, Endian::Little);
+    assert_eq!(reader.endian(), Endian::Little);
+    let (table, endian) = Table::read_auto(Cursor::new(&data)).unwrap();
+    assert_eq!(endian, Endian::Little);
+    assert_same(&reader, &table);
+}
+
+#[test]
+fn matches_a_written_table() {
+    let data = every_type::<BigEndian>();
+    let table = read::<BigEndian>(&data);
+    let written = write::<BigEndian>(&table, Default::default());
+
+    let reader = TableReader::read::<BigEndian>(Cursor::new(&written)).unwrap();
+    assert_same(&reader, &table);
+}
+
+#[test]
+fn tables_inside_of_other_files() {
+    let data = every_type::<BigEndian>();
+    let table = read::<BigEndian>(&data);
+
+    let mut embedded = vec![0xAA; 0x44];
+    embedded.extend(&data);
+    let mut cursor = Cursor::new(&embedded);
+    cursor.set_position(0x44);
+    let reader = TableReader::read::<BigEndian>(&mut cursor).unwrap();
+    assert_same(&reader, &table);
+
+    cursor.set_position(0x44);
+    let (reader, endian) = TableReader::read_auto(&mut cursor).unwrap();
+    assert_eq!(endian, Endian::Big);
+    assert_same(&reader, &table);
+
+    cursor.set_position(0x44);
+    let embedded_table = Table::read::<BigEndian>(&mut cursor).unwrap();
+    assert!(embedded_table.iter().eq(table.iter()));
+
+    // The bounds are checked from the start of the table as well.
+    let mut cursor = Cursor::new(&embedded[..embedded.len() - data.len() + 0x20]);
+    cursor.set_position(0x44);
+    assert!(TableReader::read::<BigEndian>(&mut cursor).is_err());
+}
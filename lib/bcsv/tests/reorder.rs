@@ -0,0 +1,206 @@
+mod common;
+
+use bcsv::byteorder::BigEndian;
+use bcsv::{BcsvError, DataValue, Table};
+use common::{values, words, write, Fixture};
+
+const DATA_OFFSET: usize = 0x10 + 2 * 0x0C;
+const ROW_SIZE: usize = 12;
+
+/// Rows with an id packed between bits no column covers, a string and bytes no column covers,
+/// which differ for every row so moved rows can be told apart.
+fn original() -> Vec<u8> {
+    let row = |packed: u32, string: u32, junk: &[u8; 4]| {
+        let mut row = words::<BigEndian>(&[packed, string]);
+        row.extend(junk);
+        row
+    };
+
+    Fixture {
+        columns: vec![
+            (0xAAAA, 0x0000_0FF0, 0, 4, 0),
+            (0xBBBB, 0xFFFF_FFFF, 4, 0, 6),
+        ],
+        gap: b"GAP!".to_vec(),
+        row_size: ROW_SIZE as u32,
+        rows: vec![
+            row(0xA000_003A, 0, b"row0"),
+            row(0xB000_001B, 6, b"row1"),
+            row(0xC000_002C, 10, b"row2"),
+        ],
+        pool: b"three\0one\0two\0".to_vec(),
+        trailer: b"trailing data".to_vec(),
+    }
+    .build::<BigEndian>()
+}
+
+fn read(data: &[u8]) -> Table {
+    common::read::<BigEndian>(data)
+}
+
+fn lossless(table: &Table) -> Vec<u8> {
+    write::<BigEndian>(table, common::lossless())
+}
+
+fn rows(data: &[u8], count: usize) -> Vec<&[u8]> {
+    let start = DATA_OFFSET + 4;
+    data[start..start + count * ROW_SIZE]
+        .chunks(ROW_SIZE)
+        .collect()
+}
+
+#[test]
+fn sorted_rows_keep_their_bytes() {
+    let original = original();
+    let mut table = read(&original);
+    table.sort_by_column(0).unwrap();
+    assert_eq!(values(&table), ["1", "one", "2", "two", "3", "three"]);
+
+    let written = lossless(&table);
+    assert_eq!(written.len(), original.len());
+    let before = rows(&original, 3);
+    assert_eq!(rows(&written, 3), [before[1], before[2], before[0]]);
+    assert!(written.ends_with(b"three\0one\0two\0trailing data"));
+
+    // Sorting back into the order of the file restores it.
+    let position = |row: &[DataValue]| {
+        [3, 1, 2]
+            .map(DataValue::Int32)
+            .iter()
+            .position(|x| *x == row[0])
+    };
+    table.sort_rows_by(|a, b| position(a).cmp(&position(b)));
+    assert_eq!(lossless(&table), original);
+}
+
+#[test]
+fn swapped_rows_keep_their_bytes() {
+    let original = original();
+    let mut table = read(&original);
+    table.swap_rows(0, 2).unwrap();
+
+    let written = lossless(&table);
+    let before = rows(&original, 3);
+    assert_eq!(rows(&written, 3), [before[2], before[1], before[0]]);
+    assert_eq!(
+        values(&read(&written)),
+        ["2", "two", "1", "one", "3", "three"]
+    );
+
+    table.swap_rows(2, 0).unwrap();
+    assert_eq!(lossless(&table), original);
+}
+
+#[test]
+fn retained_rows_keep_their_bytes() {
+    let original = original();
+    let mut table = read(&original);
+    table.retain(|row| row[0] != DataValue::Int32(1));
+
+    let written = lossless(&table);
+    let before = rows(&original, 3);
+    assert_eq!(rows(&written, 2), [before[0], before[2]]);
+    assert_eq!(values(&read(&written)), ["3", "three", "2", "two"]);
+}
+
+#[test]
+fn inserted_rows_start_out_empty() {
+    let original = original();
+    let mut table = read(&original);
+    table
+        .insert_row(
+            1,
+            &mut vec![DataValue::Int32(4), DataValue::OffsetString("four".into())],
+        )
+        .unwrap();
+
+    let written = lossless(&table);
+    let before = rows(&original, 3);
+    let after = rows(&written, 4);
+    assert_eq!(
+        [after[0], after[2], after[3]],
+        [before[0], before[1], before[2]]
+    );
+    assert_eq!(after[1][..4], 0x40u32.to_be_bytes());
+    assert_eq!(after[1][8..], [0; 4]);
+    assert_eq!(
+        values(&read(&written)),
+        ["3", "three", "4", "four", "1", "one", "2", "two"]
+    );
+
+    table.remove_row(1).unwrap();
+    assert_eq!(lossless(&table), original);
+}
+
+#[test]
+fn rows_out_of_bounds() {
+    let original = original();
+    let mut table = read(&original);
+    assert!(table.remove_row(3).is_none());
+    assert!(matches!(
+        table.insert_row(
+            4,
+            &mut vec![DataValue::Int32(4), DataValue::OffsetString("four".into())]
+        ),
+        Err(BcsvError::RowOutOfBounds { row: 4, rows: 3 })
+    ));
+    assert!(matches!(
+        table.swap_rows(0, 3),
+        Err(BcsvError::RowOutOfBounds { row: 3, rows: 3 })
+    ));
+    assert!(matches!(
+        table.swap_rows(5, 1),
+        Err(BcsvError::RowOutOfBounds { row: 5, rows: 3 })
+    ));
+    assert!(matches!(
+        table.sort_by_column(2),
+        Err(BcsvError::ColumnIndexOutOfBounds {
+            column: 2,
+            columns: 2
+        })
+    ));
+
+    // Nothing changes when the index is out of bounds.
+    assert_eq!(table.row_count(), 3);
+    assert_eq!(lossless(&table), original);
+
+    // Inserting right after the last row is fine.
+    table
+        .insert_row(
+            3,
+            &mut vec![DataValue::Int32(4), DataValue::OffsetString("four".into())],
+        )
+        .unwrap();
+    assert!(table.remove_row(3).unwrap()[0] == DataValue::Int32(4));
+    assert_eq!(lossless(&table), original);
+}
+
+#[test]
+fn tables_without_columns() {
+    let mut table = Table::new();
+    table.retain(|_| true);
+    table.sort_rows_by(|_, _| std::cmp::Ordering::Equal);
+    assert!(matches!(
+        table.sort_by_column(0),
+        Err(BcsvError::ColumnIndexOutOfBounds {
+            column: 0,
+            columns: 0
+        })
+    ));
+    assert_eq!(table.find_row(0, &DataValue::Int32(0)), None);
+    assert_eq!(table.row_count(), 0);
+}
+
+#[test]
+fn lookups() {
+    let table = read(&original());
+    assert_eq!(table.find_row(0, &DataValue::Int32(2)), Some(2));
+    assert_eq!(table.find_row(0, &DataValue::Int32(5)), None);
+    assert_eq!(table.find_row(2, &DataValue::Int32(2)), None);
+
+    let found = table
+        .filter_rows(|row| row[1].to_string().contains('o'))
+        .map(|row| row[0].to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(found, ["1", "2"]);
+}
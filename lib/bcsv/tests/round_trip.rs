@@ -0,0 +1,243 @@
+mod common;
+
+use bcsv::byteorder::{BigEndian, ByteOrder, LittleEndian};
+use bcsv::{DataType, DataValue, StringPool, Table, WriteOptions};
+use common::{lossless, padding, read, values, words, write, Fixture};
+
+/// Three integers packed into one word, a string and a float.
+/// The pool shares one string between rows, has a duplicate and a reference into the middle of a string.
+fn packed<T: ByteOrder>() -> Vec<u8> {
+    let row = |packed: u32, string: u32, float: f32| {
+        let mut row = words::<T>(&[packed, string]);
+        let mut bytes = [0; 4];
+        T::write_f32(&mut bytes, float);
+        row.extend(bytes);
+        row
+    };
+
+    let mut fixture = Fixture {
+        columns: vec![
+            (0x1111, 0x0000_00FF, 0, 0, 3),
+            (0x2222, 0x0000_FF00, 0, 8, 3),
+            (0x3333, 0xFFFF_0000, 0, 16, 3),
+            (0x4444, 0xFFFF_FFFF, 4, 0, 6),
+            (0x5555, 0xFFFF_FFFF, 8, 0, 2),
+        ],
+        gap: Vec::new(),
+        row_size: 12,
+        rows: vec![
+            row(0x0003_0201, 0, 1.5),
+            row(0xFFFF_07FF, 0, 2.0),
+            row(0x0000_0000, 4, -1.0),
+            row(0x1234_5678, 7, 0.25),
+            row(0x0000_0001, 1, 8.0),
+        ],
+        pool: b"abc\0de\0de\0".to_vec(),
+        trailer: Vec::new(),
+    };
+    fixture.trailer = padding(fixture.build::<T>().len(), b'@');
+    fixture.build::<T>()
+}
+
+#[test]
+fn packed_big_endian() {
+    let original = packed::<BigEndian>();
+    let table = read::<BigEndian>(&original);
+    assert_eq!(write::<BigEndian>(&table, lossless()), original);
+}
+
+#[test]
+fn packed_little_endian() {
+    let original = packed::<LittleEndian>();
+    let table = read::<LittleEndian>(&original);
+    assert_eq!(write::<LittleEndian>(&table, lossless()), original);
+}
+
+#[test]
+fn preserved_pool() {
+    let original = packed::<BigEndian>();
+    let table = read::<BigEndian>(&original);
+    let options = WriteOptions {
+        string_pool: StringPool::Preserve,
+        ..Default::default()
+    };
+    assert_eq!(write::<BigEndian>(&table, options), original);
+}
+
+#[test]
+fn deduplicated_pool() {
+    let original = packed::<BigEndian>();
+    let table = read::<BigEndian>(&original);
+    let written = write::<BigEndian>(&table, WriteOptions::default());
+
+    let data_offset = 0x10 + 5 * 0x0C;
+    let pool = &written[data_offset + 5 * 12..];
+    assert!(pool.starts_with(b"abc\0de\0bc\0@"));
+
+    let reread = read::<BigEndian>(&written);
+    assert_eq!(values(&reread), values(&table));
+}
+
+/// A header with room after the column definitions, rows with bytes and bits no column covers,
+/// and data after the string pool that isn't padding.
+fn quirks<T: ByteOrder>() -> Vec<u8> {
+    let row = |packed: u32, string: u32| {
+        let mut row = words::<T>(&[packed, string]);
+        row.extend(b"JUNK");
+        row
+    };
+
+    Fixture {
+        columns: vec![
+            (0xAAAA, 0x0000_0FF0, 0, 4, 0),
+            (0xBBBB, 0xFFFF_FFFF, 4, 0, 6),
+        ],
+        gap: b"GAPBYTES".to_vec(),
+        row_size: 12,
+        rows: vec![row(0xF000_000F, 0), row(0x8000_0AB1, 6)],
+        pool: b"first\0second\0".to_vec(),
+        trailer: b"\x01\x02trailing data".to_vec(),
+    }
+    .build::<T>()
+}
+
+#[test]
+fn header_quirks_and_trailing_data() {
+    let original = quirks::<BigEndian>();
+    let table = read::<BigEndian>(&original);
+    assert_eq!(
+        values(&table),
+        ["0", "first", "171", "second"].map(String::from)
+    );
+    assert_eq!(write::<BigEndian>(&table, lossless()), original);
+
+    let original = quirks::<LittleEndian>();
+    let table = read::<LittleEndian>(&original);
+    assert_eq!(write::<LittleEndian>(&table, lossless()), original);
+}
+
+#[test]
+fn header_quirks_are_dropped_by_default() {
+    let original = quirks::<BigEndian>();
+    let table = read::<BigEndian>(&original);
+    let written = write::<BigEndian>(&table, WriteOptions::default());
+
+    assert_eq!(written.len() % 32, 0);
+    assert_eq!(BigEndian::read_u32(&written[0x08..]), 0x10 + 2 * 0x0C);
+    assert_eq!(BigEndian::read_u32(&written[0x0C..]), 8);
+
+    let reread = read::<BigEndian>(&written);
+    assert_eq!(values(&reread), values(&table));
+}
+
+#[test]
+fn custom_padding() {
+    let mut fixture = Fixture {
+        columns: vec![(0x1234, 0xFFFF_FFFF, 0, 0, 6)],
+        gap: Vec::new(),
+        row_size: 4,
+        rows: vec![words::<BigEndian>(&[0])],
+        pool: b"text\0".to_vec(),
+        trailer: Vec::new(),
+    };
+    fixture.trailer = padding(fixture.build::<BigEndian>().len(), b'_');
+    let original = fixture.build::<BigEndian>();
+
+    let mut table = read::<BigEndian>(&original);
+    assert_eq!(write::<BigEndian>(&table, lossless()), original);
+
+    // The padding byte is kept when the pool grows.
+    table
+        .push_row(&mut vec![DataValue::OffsetString(
+            "a longer string that moves the padding".into(),
+        )])
+        .unwrap();
+    let written = write::<BigEndian>(&table, lossless());
+    assert_eq!(written.len() % 32, 0);
+    assert_eq!(written.last(), Some(&b'_'));
+}
+
+/// One offset string column whose file is padded with zeros, the second row points at the given offset.
+fn zero_padded(offset: u32) -> Vec<u8> {
+    let mut fixture = Fixture {
+        columns: vec![(0x1234, 0xFFFF_FFFF, 0, 0, 6)],
+        gap: Vec::new(),
+        row_size: 4,
+        rows: vec![words::<BigEndian>(&[0]), words::<BigEndian>(&[offset])],
+        pool: b"text\0".to_vec(),
+        trailer: Vec::new(),
+    };
+    fixture.trailer = padding(fixture.build::<BigEndian>().len(), 0);
+    fixture.build::<BigEndian>()
+}
+
+#[test]
+fn zero_padding() {
+    let original = zero_padded(1);
+    let table = read::<BigEndian>(&original);
+    assert_eq!(values(&table), ["text", "ext"]);
+    assert_eq!(write::<BigEndian>(&table, lossless()), original);
+
+    // The padding isn't kept in the pool, so saving again doesn't grow the file.
+    let options = WriteOptions {
+        string_pool: StringPool::Preserve,
+        ..Default::default()
+    };
+    let written = write::<BigEndian>(&table, options);
+    assert_eq!(written.len(), original.len());
+    assert!(written[0x24..].starts_with(b"text\0@"));
+    let reread = read::<BigEndian>(&written);
+    assert_eq!(write::<BigEndian>(&reread, options), written);
+}
+
+#[test]
+fn zero_padding_with_empty_strings() {
+    // An empty string after the last one can't be told apart from padding, so it's kept.
+    let original = zero_padded(5);
+    let table = read::<BigEndian>(&original);
+    assert_eq!(values(&table), ["text", ""]);
+    assert_eq!(write::<BigEndian>(&table, lossless()), original);
+}
+
+#[test]
+fn edits_only_touch_their_bytes() {
+    let original = quirks::<BigEndian>();
+    let mut table = read::<BigEndian>(&original);
+    table[(1, 0)] = DataValue::Int32(0x12);
+    let written = write::<BigEndian>(&table, lossless());
+
+    assert_eq!(written.len(), original.len());
+    let changed = written
+        .iter()
+        .zip(&original)
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+
+    // The packed value of the second row sits in bits 4 to 11 of its first word.
+    let row = 0x10 + 2 * 0x0C + 8 + 12;
+    assert_eq!(changed, [row + 2, row + 3]);
+}
+
+#[test]
+fn empty_tables() {
+    let table = Table::new();
+    assert_eq!(table.iter().len(), 0);
+    assert_eq!(table.iter().count(), 0);
+
+    let written = write::<BigEndian>(&table, WriteOptions::default());
+    assert_eq!(written.len(), 32);
+    let table = read::<BigEndian>(&written);
+    assert_eq!((table.row_count(), table.column_count()), (0, 0));
+    assert_eq!(write::<BigEndian>(&table, lossless()), written);
+
+    // A table with columns but no rows works the same way.
+    let mut table = Table::new();
+    table
+        .push_column(0x1234, DataType::Int32, &DataValue::Int32(0))
+        .unwrap();
+    let written = write::<BigEndian>(&table, WriteOptions::default());
+    let table = read::<BigEndian>(&written);
+    assert_eq!((table.row_count(), table.column_count()), (0, 1));
+}
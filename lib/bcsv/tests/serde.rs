@@ -0,0 +1,149 @@
+#![cfg(feature = "serde")]
+
+use bcsv::byteorder::BigEndian;
+use bcsv::{DataType, DataValue, NameHash, Table};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ObjInfo {
+    name: String,
+    l_id: i32,
+    Obj_arg0: i32,
+    SW_APPEAR: u32,
+    pos_x: f32,
+    CommonPath_ID: i16,
+    CameraSetId: i8,
+    ShapeModelNo: u16,
+    ViewGroupId: u8,
+    Disabled: bool,
+}
+
+fn objects() -> Vec<ObjInfo> {
+    vec![
+        ObjInfo {
+            name: "Kuribo".into(),
+            l_id: 0,
+            Obj_arg0: -1,
+            SW_APPEAR: u32::MAX,
+            pos_x: 1250.5,
+            CommonPath_ID: -1,
+            CameraSetId: -1,
+            ShapeModelNo: 0,
+            ViewGroupId: 0,
+            Disabled: false,
+        },
+        ObjInfo {
+            name: "スーパーキノコ".into(),
+            l_id: 7,
+            Obj_arg0: 3,
+            SW_APPEAR: 12,
+            pos_x: -3.25,
+            CommonPath_ID: i16::MAX,
+            CameraSetId: i8::MIN,
+            ShapeModelNo: u16::MAX,
+            ViewGroupId: 200,
+            Disabled: true,
+        },
+    ]
+}
+
+fn write(table: &Table) -> Vec<u8> {
+    let mut result = Vec::new();
+    table.write::<BigEndian>(&mut result).unwrap();
+    result
+}
+
+#[test]
+fn round_trip() {
+    let objects = objects();
+    let table = Table::serialize_rows(&objects, NameHash::Jgadget).unwrap();
+    let types = table.definitions().iter().map(|x| x.ty).collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        [
+            DataType::OffsetString,
+            DataType::Int32,
+            DataType::Int32,
+            DataType::UInt32,
+            DataType::Float,
+            DataType::Int16,
+            DataType::Int8,
+            DataType::Int16,
+            DataType::Int8,
+            DataType::Int32,
+        ]
+    );
+
+    let written = write(&table);
+    let read = Table::read::<BigEndian>(Cursor::new(&written)).unwrap();
+    assert_eq!(
+        read.deserialize_rows::<ObjInfo>(NameHash::Jgadget).unwrap(),
+        objects
+    );
+    assert_eq!(write(&read), written);
+}
+
+#[test]
+fn unsigned_values_keep_their_value_in_wider_columns() {
+    let mut table = Table::new();
+    for name in ["ViewGroupId", "ShapeModelNo"] {
+        table
+            .push_column(
+                NameHash::Jgadget.hash(name),
+                DataType::Int32,
+                &DataValue::Int32(0),
+            )
+            .unwrap();
+    }
+
+    #[allow(non_snake_case)]
+    #[derive(Serialize)]
+    struct Row {
+        ViewGroupId: u8,
+        ShapeModelNo: u16,
+    }
+    table
+        .set_rows(
+            &[Row {
+                ViewGroupId: 200,
+                ShapeModelNo: 60000,
+            }],
+            NameHash::Jgadget,
+        )
+        .unwrap();
+    assert!(table.row(0).unwrap() == [DataValue::Int32(200), DataValue::Int32(60000)]);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn matches_the_derive_layout() {
+    #[allow(non_snake_case, dead_code)]
+    #[derive(bcsv::BcsvRow)]
+    struct Derived {
+        name: String,
+        l_id: i32,
+        Obj_arg0: i32,
+        SW_APPEAR: u32,
+        pos_x: f32,
+        CommonPath_ID: i16,
+        CameraSetId: i8,
+        ShapeModelNo: u16,
+        ViewGroupId: u8,
+        Disabled: bool,
+    }
+
+    use bcsv::BcsvRow;
+    let table = Table::serialize_rows(&objects(), NameHash::Jgadget).unwrap();
+    let serde = table
+        .definitions()
+        .iter()
+        .map(|x| (x.name, x.ty))
+        .collect::<Vec<_>>();
+    let derive = Derived::definitions()
+        .iter()
+        .map(|x| (x.name, x.ty))
+        .collect::<Vec<_>>();
+    assert_eq!(serde, derive);
+}
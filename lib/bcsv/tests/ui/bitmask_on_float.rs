@@ -0,0 +1,7 @@
+#[derive(bcsv::BcsvRow)]
+struct Row {
+    #[bcsv(bitmask = 0xFF)]
+    value: f32,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[derive(bcsv::BcsvRow)]
+struct Row {
+    name: i32,
+    #[bcsv(name = "name")]
+    other: i32,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[derive(bcsv::BcsvRow)]
+struct Row {
+    first: i32,
+    #[bcsv(offset = 2)]
+    second: i16,
+}
+
+fn main() {}
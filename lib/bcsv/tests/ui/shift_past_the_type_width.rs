@@ -0,0 +1,7 @@
+#[derive(bcsv::BcsvRow)]
+struct Row {
+    #[bcsv(shift = 8)]
+    flags: u8,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(bcsv::BcsvRow)]
+struct Row {
+    #[bcsv(shift = 32)]
+    flags: u32,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+//! # Byte Order
+//!
+//! The byte order shared by the libraries in this workspace,
+//! so an order that was detected for one file can be passed on to the tools for another.
+
+/// The byte order a file was detected to be encoded in.
+/// GameCube and Wii games use big endian, while later games use little endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
@@ -1,86 +1,1092 @@
 use crate::header::RarcHeader;
-use crate::node::{self, DirectoryNode, FileAttributes, FileNode};
-use crate::string_table::StringTable;
-use crate::{RarcError, Result};
-use byteorder::ByteOrder;
-use std::collections::BTreeMap;
+use crate::node::{self, create_tag, DirectoryNode, FileAttributes, FileNode, LoadClass};
+use crate::reader::{ArchiveReader, FileReader, Source};
+use crate::string_table::{StringTable, StringTableWriter};
+use crate::{align, Endian, RarcError, Result};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Read, Seek, Write};
-use std::path::Path;
+use std::io::{self, BufRead, BufReader, BufWriter, Cursor, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use yaz0::{CompressionLevel, Format};
+
+/// New contents for a file in an archive.
+pub enum FileData {
+    /// The contents are held in memory.
+    Memory(Vec<u8>),
+    /// The contents are read from the file at the given path, when the archive is written.
+    Path(PathBuf),
+}
+
+impl From<Vec<u8>> for FileData {
+    fn from(value: Vec<u8>) -> Self {
+        FileData::Memory(value)
+    }
+}
+
+impl From<&[u8]> for FileData {
+    fn from(value: &[u8]) -> Self {
+        FileData::Memory(value.into())
+    }
+}
+
+impl From<PathBuf> for FileData {
+    fn from(value: PathBuf) -> Self {
+        FileData::Path(value)
+    }
+}
+
+impl From<&Path> for FileData {
+    fn from(value: &Path) -> Self {
+        FileData::Path(value.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Directory,
+    File,
+}
+
+enum Contents {
+    Archived {
+        offset: u32,
+        compression: Option<Format>,
+    },
+    Replaced(FileData),
+}
+
+impl Contents {
+    fn compression(&self) -> Option<Format> {
+        match self {
+            Contents::Archived { compression, .. } => *compression,
+            Contents::Replaced(_) => None,
+        }
+    }
+}
 
 enum IndexEntry {
     Directory {
-        files: u16,
+        position: u32,
+        /// The index of the directory node this directory was read from.
+        node: u32,
+        tag: Option<[u8; 4]>,
     },
     File {
-        offset: u32,
+        id: u16,
+        contents: Contents,
         size: u32,
         attributes: FileAttributes,
+        position: u32,
+        /// The order of replaced data within its load class, archived data keeps its offset order.
+        data_position: u32,
     },
 }
 
+impl IndexEntry {
+    fn position(&self) -> u32 {
+        match self {
+            IndexEntry::Directory { position, .. } => *position,
+            IndexEntry::File { position, .. } => *position,
+        }
+    }
+
+    fn kind(&self) -> EntryKind {
+        match self {
+            IndexEntry::Directory { .. } => EntryKind::Directory,
+            IndexEntry::File { .. } => EntryKind::File,
+        }
+    }
+}
+
+/// How the name hashes of directories and files are checked when reading an archive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashCheck {
+    /// Reading fails with [`RarcError::HashMismatch`] on the first stale hash.
+    Strict,
+    /// Stale hashes are collected as warnings, which can be retrieved using [`Archive::warnings`].
+    /// This is the default, since archives written by some community tools have stale hashes.
+    #[default]
+    Lenient,
+}
+
+/// Options that change how an archive is read.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadOptions {
+    pub hash_check: HashCheck,
+}
+
 pub struct Archive<F: Read + Seek> {
     index: BTreeMap<String, IndexEntry>,
     reader: F,
+    root: String,
+    data_offset: u64,
+    next_file: u16,
+    keep_synced: bool,
+    compression: Option<Format>,
+    compression_level: CompressionLevel,
+    warnings: Vec<RarcError>,
 }
 
 impl<F: Read + Seek> Archive<F> {
-    pub fn read<T: ByteOrder>(mut reader: F) -> Result<Self> {
+    pub fn read<T: ByteOrder>(reader: F) -> Result<Self> {
+        Archive::read_with::<T>(reader, ReadOptions::default())
+    }
+
+    /// Reads an archive, detecting its byte order from the header.
+    /// The detected order is returned as well, so the archive can be written back the same way.
+    pub fn read_auto(mut reader: F) -> Result<(Self, Endian)> {
+        let endian = RarcHeader::detect(&mut reader)?;
+        let archive = match endian {
+            Endian::Big => Archive::read::<BigEndian>(reader)?,
+            Endian::Little => Archive::read::<LittleEndian>(reader)?,
+        };
+        Ok((archive, endian))
+    }
+
+    pub fn read_with<T: ByteOrder>(mut reader: F, options: ReadOptions) -> Result<Self> {
         let header = RarcHeader::read::<T>(&mut reader)?;
 
+        let mut archive = Archive {
+            index: BTreeMap::new(),
+            reader,
+            root: String::new(),
+            data_offset: header.position(header.data_offset),
+            next_file: header.next_file,
+            keep_synced: header.keep_synced,
+            compression: None,
+            compression_level: CompressionLevel::default(),
+            warnings: Vec::new(),
+        };
+
         if header.directory_nodes == 0 {
-            return Ok(Archive {
-                index: BTreeMap::new(),
-                reader,
-            });
+            return Ok(archive);
         }
 
-        let mut index = BTreeMap::new();
-        let table = StringTable::read(&mut reader, &header)?;
+        let table = StringTable::read(&mut archive.reader, &header)?;
 
-        let directories = node::read_directory_nodes(&mut reader, &header)?;
-        let files = node::read_file_nodes(&mut reader, &header)?;
+        let directories = node::read_directory_nodes(&mut archive.reader, &header)?;
+        let files = node::read_file_nodes(&mut archive.reader, &header)?;
 
         if &directories[0].tag != b"ROOT" {
             return Err(RarcError::FirstDirectoryNotRoot);
         }
 
-        read_directory::<T>(0, "/".into(), &directories, &files, &table, &mut index)?;
+        let root_name = T::read_u32(bytemuck::bytes_of(&directories[0].name_offset));
+        archive.root = table
+            .string_at(root_name as usize)
+            .unwrap_or_default()
+            .into();
+
+        let mut visited = vec![false; directories.len()];
+        visited[0] = true;
+
+        TreeReader {
+            directories: &directories,
+            files: &files,
+            table: &table,
+            visited,
+            hash_check: options.hash_check,
+            warnings: &mut archive.warnings,
+            index: &mut archive.index,
+        }
+        .read_directory::<T>(0, 0, "/".into())?;
 
-        Ok(Archive { index, reader })
+        Ok(archive)
     }
 
-    pub fn write<T: ByteOrder>(&self, mut writer: impl Write) -> Result<()> {
-        todo!()
+    /// Gets the problems that were tolerated while reading the archive,
+    /// like stale name hashes when reading with [`HashCheck::Lenient`].
+    pub fn warnings(&self) -> &[RarcError] {
+        &self.warnings
     }
 
-    pub fn save<T: ByteOrder>(&self, path: impl AsRef<Path>) -> Result<()> {
+    /// Writes this archive into the given writer.
+    /// If a compression format is set, the whole archive gets compressed using it.
+    ///
+    /// Unlike the `&self` signature of the legacy `jsystem` crate, this takes `&mut self`,
+    /// since unchanged contents are copied out of the reader the archive was read from
+    /// and file IDs get renumbered if the archive keeps them synced.
+    pub fn write<T: ByteOrder>(&mut self, mut writer: impl Write) -> Result<()> {
+        match self.compression {
+            Some(format) => {
+                let mut buffer = Vec::new();
+                self.write_archive::<T>(&mut buffer)?;
+                writer.write_all(&yaz0::compress(&buffer, format, self.compression_level))?;
+                Ok(())
+            }
+            None => self.write_archive::<T>(writer),
+        }
+    }
+
+    fn write_archive<T: ByteOrder>(&mut self, mut writer: impl Write) -> Result<()> {
+        if self.index.is_empty() {
+            let header = RarcHeader {
+                base: 0,
+                size: 0x40,
+                data_offset: 0x20,
+                data_length: 0,
+                mram: 0,
+                aram: 0,
+                dvd: 0,
+
+                directory_nodes: 0,
+                directory_offset: 0x20,
+                file_nodes: 0,
+                file_offset: 0x20,
+                string_size: 0,
+                string_offset: 0x20,
+                next_file: self.next_file,
+                keep_synced: self.keep_synced,
+            };
+            return header.write::<T>(writer);
+        }
+
+        let mut data = data_order(&self.index)
+            .into_iter()
+            .filter_map(|path| match &self.index[path] {
+                IndexEntry::File {
+                    contents,
+                    size,
+                    attributes,
+                    ..
+                } => Some((path, contents, *size, *attributes)),
+                IndexEntry::Directory { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut encoded = HashMap::new();
+        for (path, contents, size, attributes) in &mut data {
+            let compression = attributes.compression();
+            if contents.compression() == compression {
+                continue;
+            }
+
+            let reader = open_contents(&mut self.reader, self.data_offset, contents, *size)?;
+            let mut buffer = read_all(reader)?;
+            if contents.compression().is_some() {
+                buffer = yaz0::decompress(&buffer)?;
+            }
+            if let Some(format) = compression {
+                buffer = yaz0::compress(&buffer, format, self.compression_level);
+            }
+
+            *size = u32::try_from(buffer.len()).map_err(|_| RarcError::FileTooLarge)?;
+            encoded.insert(*path, buffer);
+        }
+
+        let mut data_sizes = HashMap::with_capacity(data.len());
+        let mut data_offsets = HashMap::with_capacity(data.len());
+        let mut data_length = 0;
+        let mut mram = 0;
+        let mut aram = 0;
+        let mut dvd = 0;
+        for (path, _, size, attributes) in &data {
+            data_offsets.insert(*path, data_length);
+            data_sizes.insert(*path, *size);
+
+            let size = align(*size as usize) as u32;
+            match attributes.load_class() {
+                Some(LoadClass::Mram) => mram += size,
+                Some(LoadClass::Aram) => aram += size,
+                Some(LoadClass::Dvd) => dvd += size,
+                None => {}
+            }
+            data_length += size;
+        }
+
+        let mut children: HashMap<&str, Vec<(&str, &IndexEntry)>> = HashMap::new();
+        for (path, entry) in &self.index {
+            if path != "/" {
+                children
+                    .entry(parent_path(path))
+                    .or_default()
+                    .push((path, entry));
+            }
+        }
+        for entries in children.values_mut() {
+            entries.sort_by_key(|(path, entry)| (entry.position(), *path));
+        }
+
+        // Directories keep the order of their nodes. New ones are added after them in depth-first order,
+        // which is the order Nintendo's archives use.
+        let mut order = Vec::new();
+        let mut pending = vec!["/"];
+        while let Some(path) = pending.pop() {
+            order.push(path);
+            if let Some(entries) = children.get(path) {
+                pending.extend(
+                    entries
+                        .iter()
+                        .rev()
+                        .filter(|(_, entry)| entry.kind() == EntryKind::Directory)
+                        .map(|(path, _)| *path),
+                );
+            }
+        }
+        order.sort_by_key(|path| match self.index.get(*path) {
+            Some(IndexEntry::Directory { node, .. }) => *node,
+            _ => u32::MAX,
+        });
+        let indices = order
+            .iter()
+            .enumerate()
+            .map(|(i, path)| (*path, i as u32))
+            .collect::<HashMap<_, _>>();
+
+        let mut strings = StringTableWriter::default();
+        let dot = name_offset(strings.write_str("."))?;
+        let dotdot = name_offset(strings.write_str(".."))?;
+
+        let mut directory_nodes = Vec::with_capacity(order.len());
+        let mut file_nodes = Vec::new();
+        let mut synced_ids = Vec::new();
+
+        for (current, path) in order.iter().copied().enumerate() {
+            let entries = children.get(path).map(Vec::as_slice).unwrap_or_default();
+
+            let (name, tag, parent) = if path == "/" {
+                (self.root.as_str(), *b"ROOT", u32::MAX)
+            } else {
+                let name = file_name(path);
+                let tag = match self.index.get(path) {
+                    Some(IndexEntry::Directory { tag: Some(tag), .. }) => *tag,
+                    _ => create_tag(name),
+                };
+                (name, tag, indices[parent_path(path)])
+            };
+
+            let file_count =
+                u16::try_from(entries.len() + 2).map_err(|_| RarcError::TooManyFiles)?;
+            directory_nodes.push(DirectoryNode::new::<T>(
+                tag,
+                strings.write_str(name),
+                node::name_hash(name),
+                file_count,
+                file_nodes.len() as u32,
+            ));
+
+            for (child, entry) in entries {
+                let name = file_name(child);
+                let offset = name_offset(strings.write_str(name))?;
+
+                file_nodes.push(match entry {
+                    IndexEntry::Directory { .. } => FileNode::new::<T>(
+                        u16::MAX,
+                        node::name_hash(name),
+                        FileAttributes::DIRECTORY,
+                        offset,
+                        indices[child],
+                        0x10,
+                    ),
+                    IndexEntry::File { id, attributes, .. } => {
+                        let id = if self.keep_synced {
+                            let id = u16::try_from(file_nodes.len())
+                                .ok()
+                                .filter(|x| *x != u16::MAX)
+                                .ok_or(RarcError::TooManyFiles)?;
+                            synced_ids.push((child.to_string(), id));
+                            id
+                        } else {
+                            *id
+                        };
+
+                        FileNode::new::<T>(
+                            id,
+                            node::name_hash(name),
+                            *attributes,
+                            offset,
+                            data_offsets[child],
+                            data_sizes[child],
+                        )
+                    }
+                });
+            }
+
+            file_nodes.push(FileNode::new::<T>(
+                u16::MAX,
+                node::name_hash("."),
+                FileAttributes::DIRECTORY,
+                dot,
+                current as u32,
+                0x10,
+            ));
+            file_nodes.push(FileNode::new::<T>(
+                u16::MAX,
+                node::name_hash(".."),
+                FileAttributes::DIRECTORY,
+                dotdot,
+                parent,
+                0x10,
+            ));
+        }
+
+        let next_file = if self.keep_synced {
+            u16::try_from(file_nodes.len()).map_err(|_| RarcError::TooManyFiles)?
+        } else {
+            self.next_file
+        };
+
+        let strings = strings.finish();
+
+        let directory_offset = 0x20;
+        let file_offset = directory_offset + align(directory_nodes.len() * 0x10) as u32;
+        let string_offset = file_offset + align(file_nodes.len() * 0x14) as u32;
+        let data_offset = string_offset + strings.len() as u32;
+
+        let header = RarcHeader {
+            base: 0,
+            size: 0x20 + data_offset + data_length,
+            data_offset,
+            data_length,
+            mram,
+            aram,
+            dvd,
+
+            directory_nodes: directory_nodes.len() as u32,
+            directory_offset,
+            file_nodes: file_nodes.len() as u32,
+            file_offset,
+            string_size: strings.len() as u32,
+            string_offset,
+            next_file,
+            keep_synced: self.keep_synced,
+        };
+        header.write::<T>(&mut writer)?;
+
+        let nodes = bytemuck::cast_slice(&directory_nodes);
+        writer.write_all(nodes)?;
+        write_padding(&mut writer, nodes.len())?;
+
+        let nodes = bytemuck::cast_slice(&file_nodes);
+        writer.write_all(nodes)?;
+        write_padding(&mut writer, nodes.len())?;
+
+        writer.write_all(&strings)?;
+
+        for (path, contents, size, _) in data {
+            match encoded.get(path) {
+                Some(buffer) => writer.write_all(buffer)?,
+                None => {
+                    let mut reader =
+                        open_contents(&mut self.reader, self.data_offset, contents, size)?;
+                    if io::copy(&mut reader, &mut writer)? != size as u64 {
+                        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+                    }
+                }
+            }
+
+            write_padding(&mut writer, size as usize)?;
+        }
+
+        for (path, id) in synced_ids {
+            if let Some(IndexEntry::File { id: current, .. }) = self.index.get_mut(&path) {
+                *current = id;
+            }
+        }
+        self.next_file = next_file;
+
+        Ok(())
+    }
+
+    pub fn save<T: ByteOrder>(&mut self, path: impl AsRef<Path>) -> Result<()> {
         let file = File::create(path)?;
-        let writer = BufWriter::new(file);
-        self.write::<T>(writer)
+        let mut writer = BufWriter::new(file);
+        self.write::<T>(&mut writer)?;
+        writer.flush()?;
+        Ok(())
     }
 
-    pub fn open_file(&mut self, path: &str) -> Option<ArchivedFile<'_>> {
-        match self.index.get_mut(path)? {
+    pub fn open_file(&mut self, path: &str) -> Option<ArchivedFile<'_, F>> {
+        match self.index.get_mut(&normalize_path(path))? {
             IndexEntry::Directory { .. } => None,
-            entry => Some(ArchivedFile { entry }),
+            entry => Some(ArchivedFile {
+                entry,
+                reader: &mut self.reader,
+                data_offset: self.data_offset,
+            }),
+        }
+    }
+
+    /// Gets the format the archive was compressed with when it was read and that is used when it is written.
+    pub fn compression(&self) -> Option<Format> {
+        self.compression
+    }
+
+    pub fn set_compression(&mut self, format: Option<Format>) {
+        self.compression = format;
+    }
+
+    pub fn compression_level(&self) -> CompressionLevel {
+        self.compression_level
+    }
+
+    pub fn set_compression_level(&mut self, level: CompressionLevel) {
+        self.compression_level = level;
+    }
+
+    /// Gets whether the IDs of files are kept in sync with the indices of their file nodes.
+    pub fn keep_synced(&self) -> bool {
+        self.keep_synced
+    }
+
+    /// Sets whether the IDs of files are kept in sync with the indices of their file nodes.
+    /// When enabled, every file gets renumbered when the archive is written.
+    /// Otherwise, existing IDs are preserved and new files get the next free ID.
+    pub fn set_keep_synced(&mut self, value: bool) {
+        self.keep_synced = value;
+    }
+
+    /// Gets the next file ID as it is stored in the header.
+    /// Created files get this ID, or one past the highest ID in use if that is higher.
+    pub fn next_file_id(&self) -> u16 {
+        self.next_file
+    }
+
+    pub fn root_name(&self) -> &str {
+        &self.root
+    }
+
+    pub fn set_root_name(&mut self, name: &str) -> Result<()> {
+        check_name(name)?;
+        self.root = name.into();
+        Ok(())
+    }
+
+    /// Gets the 4 byte tag of a directory, which the game uses to tell resource folders apart.
+    /// Directories without an explicit tag get one generated from their name, see [`create_tag`].
+    pub fn directory_tag(&self, path: &str) -> Option<[u8; 4]> {
+        let path = normalize_path(path);
+        match self.index.get(&path)? {
+            IndexEntry::Directory { .. } if path == "/" => Some(*b"ROOT"),
+            IndexEntry::Directory { tag: Some(tag), .. } => Some(*tag),
+            IndexEntry::Directory { tag: None, .. } => Some(create_tag(file_name(&path))),
+            IndexEntry::File { .. } => None,
+        }
+    }
+
+    /// Gets the tag of a directory, unless it is generated from its name.
+    /// Directories read from an archive only have a custom tag if their stored tag differs from the generated one.
+    pub fn custom_directory_tag(&self, path: &str) -> Option<[u8; 4]> {
+        let path = normalize_path(path);
+        match self.index.get(&path)? {
+            IndexEntry::Directory { tag, .. } if path != "/" => *tag,
+            _ => None,
+        }
+    }
+
+    /// Sets the tag of a directory, or makes it generate its tag from its name again when `None` is given.
+    /// The tag of the root directory is always `ROOT` and can't be changed.
+    pub fn set_directory_tag(&mut self, path: &str, tag: Option<[u8; 4]>) -> Result<()> {
+        let path = normalize_path(path);
+        if path == "/" {
+            return Err(RarcError::RootDirectory);
         }
+
+        match self.index.get_mut(&path) {
+            Some(IndexEntry::Directory { tag: current, .. }) => {
+                *current = tag;
+                Ok(())
+            }
+            Some(IndexEntry::File { .. }) => Err(RarcError::NotADirectory { path }),
+            None => Err(RarcError::NotFound { path }),
+        }
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.index.contains_key(&normalize_path(path))
+    }
+
+    pub fn is_directory(&self, path: &str) -> bool {
+        matches!(
+            self.index.get(&normalize_path(path)),
+            Some(IndexEntry::Directory { .. })
+        )
+    }
+
+    /// Creates a directory and all of its missing parent directories.
+    pub fn create_directory(&mut self, path: &str) -> Result<()> {
+        let path = normalize_path(path);
+        self.create_parents(&path)?;
+
+        match self.index.get(&path) {
+            Some(IndexEntry::Directory { .. }) => Ok(()),
+            Some(IndexEntry::File { .. }) => Err(RarcError::NotADirectory { path }),
+            None => {
+                check_name(file_name(&path))?;
+                self.index.insert(
+                    path,
+                    IndexEntry::Directory {
+                        position: u32::MAX,
+                        node: u32::MAX,
+                        tag: None,
+                    },
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Creates a file with the given contents, creating any missing parent directories.
+    /// If the file already exists, its contents get replaced instead.
+    pub fn create_file(&mut self, path: &str, data: impl Into<FileData>) -> Result<()> {
+        let path = normalize_path(path);
+        let data = data.into();
+        let size = data_size(&data)?;
+        self.create_parents(&path)?;
+
+        match self.index.get_mut(&path) {
+            Some(IndexEntry::Directory { .. }) => Err(RarcError::IsADirectory { path }),
+            Some(IndexEntry::File {
+                contents,
+                size: current,
+                ..
+            }) => {
+                *contents = Contents::Replaced(data);
+                *current = size;
+                Ok(())
+            }
+            None => {
+                check_name(file_name(&path))?;
+
+                // The stored next id can be stale, so it is moved past the ids that are in use.
+                let id = self
+                    .index
+                    .values()
+                    .filter_map(|entry| match entry {
+                        IndexEntry::File { id, .. } => Some(id.saturating_add(1)),
+                        IndexEntry::Directory { .. } => None,
+                    })
+                    .fold(self.next_file, u16::max);
+                if id == u16::MAX {
+                    return Err(RarcError::TooManyFiles);
+                }
+                self.next_file = id + 1;
+
+                self.index.insert(
+                    path,
+                    IndexEntry::File {
+                        id,
+                        contents: Contents::Replaced(data),
+                        size,
+                        attributes: FileAttributes::FILE | FileAttributes::PRELOAD_TO_MRAM,
+                        position: u32::MAX,
+                        data_position: u32::MAX,
+                    },
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes a file or a directory with all of its contents.
+    /// Returns `false` if nothing exists at the given path.
+    pub fn remove(&mut self, path: &str) -> Result<bool> {
+        let path = normalize_path(path);
+        if path == "/" {
+            return Err(RarcError::RootDirectory);
+        }
+
+        match self.index.remove(&path) {
+            Some(IndexEntry::Directory { .. }) => {
+                let prefix = path + "/";
+                self.index.retain(|key, _| !key.starts_with(&prefix));
+                Ok(true)
+            }
+            Some(IndexEntry::File { .. }) => Ok(true),
+            None => Ok(false),
+        }
+    }
+
+    /// Moves a file or a directory with all of its contents to a new path.
+    pub fn mv(&mut self, old: &str, new: &str) -> Result<()> {
+        let old = normalize_path(old);
+        let new = normalize_path(new);
+
+        if old == "/" || new == "/" {
+            return Err(RarcError::RootDirectory);
+        }
+
+        if !self.index.contains_key(&old) {
+            return Err(RarcError::NotFound { path: old });
+        }
+
+        if self.index.contains_key(&new) {
+            return Err(RarcError::AlreadyExists { path: new });
+        }
+
+        let prefix = old.clone() + "/";
+        if new.starts_with(&prefix) {
+            return Err(RarcError::MoveIntoItself { path: old });
+        }
+
+        check_name(file_name(&new))?;
+        self.create_parents(&new)?;
+
+        let moved = self
+            .index
+            .keys()
+            .filter(|key| key.starts_with(&prefix))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in moved {
+            let entry = self.index.remove(&key).unwrap();
+            self.index
+                .insert(format!("{}{}", new, &key[old.len()..]), entry);
+        }
+
+        let entry = self.index.remove(&old).unwrap();
+        self.index.insert(new, entry);
+        Ok(())
+    }
+
+    /// Iterates over every entry, starting with the root directory, in the order their nodes are stored in.
+    /// Entries that were added after the archive was read come last, sorted by their path.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, EntryKind)> {
+        let mut entries = self.index.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(path, entry)| (*path != "/", entry.position(), *path));
+        entries
+            .into_iter()
+            .map(|(path, entry)| (path.as_str(), entry.kind()))
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.iter()
+            .filter(|(_, kind)| *kind == EntryKind::File)
+            .map(|(path, _)| path)
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = &str> {
+        self.iter()
+            .filter(|(_, kind)| *kind == EntryKind::Directory)
+            .map(|(path, _)| path)
+    }
+
+    /// Gets every entry except the root directory, in the order their nodes are written in.
+    #[cfg(feature = "manifest")]
+    pub(crate) fn ordered_entries(&self) -> Vec<(&str, EntryKind)> {
+        self.iter().filter(|(path, _)| *path != "/").collect()
+    }
+
+    /// Gets the paths of all files in the order their data is written in.
+    #[cfg(feature = "manifest")]
+    pub(crate) fn data_order(&self) -> Vec<&str> {
+        data_order(&self.index)
+    }
+
+    /// Sets the position replaced data is sorted by within its load class.
+    #[cfg(feature = "manifest")]
+    pub(crate) fn set_data_position(&mut self, path: &str, value: u32) {
+        if let Some(IndexEntry::File { data_position, .. }) = self.index.get_mut(path) {
+            *data_position = value;
+        }
+    }
+
+    /// Sets the position an entry is sorted by within its directory.
+    #[cfg(feature = "manifest")]
+    pub(crate) fn set_position(&mut self, path: &str, value: u32) {
+        match self.index.get_mut(path) {
+            Some(IndexEntry::Directory { position, .. }) => *position = value,
+            Some(IndexEntry::File { position, .. }) => *position = value,
+            None => {}
+        }
+    }
+
+    #[cfg(feature = "manifest")]
+    pub(crate) fn set_next_file_id(&mut self, value: u16) {
+        self.next_file = value;
+    }
+
+    fn stored_slice<'b>(&'b self, path: &str, buffer: &'b [u8]) -> Option<&'b [u8]> {
+        match self.index.get(&normalize_path(path))? {
+            IndexEntry::File {
+                contents: Contents::Archived { offset, .. },
+                size,
+                ..
+            } => {
+                let start = self.data_offset as usize + *offset as usize;
+                buffer.get(start..(start + *size as usize))
+            }
+            IndexEntry::File {
+                contents: Contents::Replaced(FileData::Memory(buffer)),
+                ..
+            } => Some(buffer),
+            _ => None,
+        }
+    }
+
+    fn create_parents(&mut self, path: &str) -> Result<()> {
+        let mut end = 0;
+        while let Some(idx) = path[(end + 1)..].find('/') {
+            end += idx + 1;
+            let parent = &path[..end];
+
+            match self.index.get(parent) {
+                Some(IndexEntry::Directory { .. }) => {}
+                Some(IndexEntry::File { .. }) => {
+                    return Err(RarcError::NotADirectory {
+                        path: parent.into(),
+                    })
+                }
+                None => {
+                    check_name(file_name(parent))?;
+                    self.index.insert(
+                        parent.into(),
+                        IndexEntry::Directory {
+                            position: u32::MAX,
+                            node: u32::MAX,
+                            tag: None,
+                        },
+                    );
+                }
+            }
+        }
+
+        self.index
+            .entry("/".into())
+            .or_insert(IndexEntry::Directory {
+                position: 0,
+                node: 0,
+                tag: None,
+            });
+        Ok(())
+    }
+}
+
+impl Archive<io::Empty> {
+    /// Creates a new empty archive, whose root directory has the given name.
+    pub fn new(root: &str) -> Result<Self> {
+        check_name(root)?;
+        Ok(Archive {
+            index: BTreeMap::new(),
+            reader: io::empty(),
+            root: root.into(),
+            data_offset: 0,
+            next_file: 0,
+            keep_synced: true,
+            compression: None,
+            compression_level: CompressionLevel::default(),
+            warnings: Vec::new(),
+        })
+    }
+}
+
+impl Archive<Cursor<Vec<u8>>> {
+    /// Reads an archive from an in-memory buffer.
+    /// Yaz0 and Yay0 compressed archives get decompressed first.
+    pub fn from_vec<T: ByteOrder>(buffer: Vec<u8>) -> Result<Self> {
+        Archive::from_vec_with::<T>(buffer, ReadOptions::default())
+    }
+
+    pub fn from_vec_with<T: ByteOrder>(buffer: Vec<u8>, options: ReadOptions) -> Result<Self> {
+        match Format::detect(&buffer) {
+            Some(format) => {
+                let buffer = yaz0::decompress(&buffer)?;
+                let mut archive = Archive::read_with::<T>(Cursor::new(buffer), options)?;
+                archive.compression = Some(format);
+                Ok(archive)
+            }
+            None => Archive::read_with::<T>(Cursor::new(buffer), options),
+        }
+    }
+
+    /// Like [`Archive::from_vec`], but detects the byte order from the header.
+    pub fn from_vec_auto(buffer: Vec<u8>) -> Result<(Self, Endian)> {
+        match Format::detect(&buffer) {
+            Some(format) => {
+                let buffer = yaz0::decompress(&buffer)?;
+                let (mut archive, endian) = Archive::read_auto(Cursor::new(buffer))?;
+                archive.compression = Some(format);
+                Ok((archive, endian))
+            }
+            None => Archive::read_auto(Cursor::new(buffer)),
+        }
+    }
+}
+
+impl<A: AsRef<[u8]>> Archive<Cursor<A>> {
+    /// Gets the contents of a file as they are stored in the archive, without copying them.
+    /// Returns `None` if there is no file at the given path or it was replaced with a path.
+    pub fn file_slice(&self, path: &str) -> Option<&[u8]> {
+        self.stored_slice(path, self.reader.get_ref().as_ref())
     }
 }
 
-impl Archive<BufReader<File>> {
+impl Archive<ArchiveReader> {
+    /// Opens the archive at the given path.
+    /// Uncompressed archives are read from the file as needed,
+    /// while Yaz0 and Yay0 compressed archives get decompressed into memory.
     pub fn open<T: ByteOrder>(path: impl AsRef<Path>) -> Result<Self> {
+        Archive::open_with::<T>(path, ReadOptions::default())
+    }
+
+    pub fn open_with<T: ByteOrder>(path: impl AsRef<Path>, options: ReadOptions) -> Result<Self> {
         let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        Archive::read::<T>(reader)
+        let mut reader = BufReader::new(file);
+
+        if Format::detect(reader.fill_buf()?).is_some() {
+            let mut buffer = Vec::new();
+            reader.read_to_end(&mut buffer)?;
+            Ok(Archive::from_vec_with::<T>(buffer, options)?.map_reader(ArchiveReader::Memory))
+        } else {
+            Archive::read_with::<T>(ArchiveReader::File(reader), options)
+        }
+    }
+
+    /// Like [`Archive::open`], but detects the byte order from the header.
+    pub fn open_auto(path: impl AsRef<Path>) -> Result<(Self, Endian)> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+
+        if Format::detect(reader.fill_buf()?).is_some() {
+            let mut buffer = Vec::new();
+            reader.read_to_end(&mut buffer)?;
+            let (archive, endian) = Archive::from_vec_auto(buffer)?;
+            Ok((archive.map_reader(ArchiveReader::Memory), endian))
+        } else {
+            Archive::read_auto(ArchiveReader::File(reader))
+        }
+    }
+
+    /// Maps the archive at the given path into memory.
+    /// Uncompressed archives are read directly from the mapping,
+    /// while Yaz0 and Yay0 compressed archives get decompressed into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the archive is in use, see [`memmap2::Mmap::map`].
+    #[cfg(feature = "mmap")]
+    pub unsafe fn map<T: ByteOrder>(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path)?;
+        let mmap = memmap2::Mmap::map(&file)?;
+
+        if Format::detect(&mmap).is_some() {
+            Ok(Archive::from_vec::<T>(mmap.to_vec())?.map_reader(ArchiveReader::Memory))
+        } else {
+            Archive::read::<T>(ArchiveReader::Mapped(Cursor::new(mmap)))
+        }
+    }
+
+    /// Gets the contents of a file as they are stored in the archive, without copying them.
+    /// Returns `None` if there is no file at the given path or it was replaced with a path,
+    /// or if the archive is read from a file instead of memory.
+    pub fn file_slice(&self, path: &str) -> Option<&[u8]> {
+        self.stored_slice(path, self.reader.as_slice()?)
+    }
+}
+
+impl<F: Read + Seek> Archive<F> {
+    fn map_reader<G: Read + Seek>(self, f: impl FnOnce(F) -> G) -> Archive<G> {
+        Archive {
+            index: self.index,
+            reader: f(self.reader),
+            root: self.root,
+            data_offset: self.data_offset,
+            next_file: self.next_file,
+            keep_synced: self.keep_synced,
+            compression: self.compression,
+            compression_level: self.compression_level,
+            warnings: self.warnings,
+        }
     }
 }
 
-pub struct ArchivedFile<'a> {
+pub struct ArchivedFile<'a, F: Read + Seek> {
     entry: &'a mut IndexEntry,
+    reader: &'a mut F,
+    data_offset: u64,
 }
 
-impl<'a> ArchivedFile<'a> {
+impl<'a, F: Read + Seek> ArchivedFile<'a, F> {
+    /// Creates a reader over the contents of this file.
+    /// Files that weren't replaced are read directly out of the archive,
+    /// unless they are compressed, in which case they get decompressed into memory first.
+    pub fn reader(&mut self) -> Result<FileReader<'_, F>> {
+        if self.stored_compression().is_some() {
+            let buffer = yaz0::decompress(&self.read_raw_to_vec()?)?;
+            let size = buffer.len() as u64;
+            return Ok(FileReader::new(
+                Source::Owned(Cursor::new(buffer)),
+                0,
+                size,
+            )?);
+        }
+
+        self.raw_reader()
+    }
+
+    /// Creates a reader over the contents of this file as they are stored in the archive.
+    /// Unlike [`ArchivedFile::reader`], compressed files are not decompressed.
+    pub fn raw_reader(&mut self) -> Result<FileReader<'_, F>> {
+        if let IndexEntry::File { contents, size, .. } = &*self.entry {
+            open_contents(&mut *self.reader, self.data_offset, contents, *size)
+        } else {
+            panic!("Expected File entry");
+        }
+    }
+
+    /// Reads the whole contents of this file into a vec, decompressing them if needed.
+    pub fn read_to_vec(&mut self) -> Result<Vec<u8>> {
+        read_all(self.reader()?)
+    }
+
+    /// Reads the whole contents of this file into a vec, as they are stored in the archive.
+    pub fn read_raw_to_vec(&mut self) -> Result<Vec<u8>> {
+        read_all(self.raw_reader()?)
+    }
+
+    /// Gets the compression format the contents of this file are currently stored with.
+    /// This can differ from the one set in the attributes, until the archive is written.
+    pub fn stored_compression(&self) -> Option<Format> {
+        if let IndexEntry::File { contents, .. } = &self.entry {
+            contents.compression()
+        } else {
+            panic!("Expected File entry");
+        }
+    }
+
+    /// Gets the ID that the game uses to load this file.
+    /// If the archive keeps IDs synced, this is updated whenever the archive is written.
+    pub fn id(&self) -> u16 {
+        if let IndexEntry::File { id, .. } = &self.entry {
+            *id
+        } else {
+            panic!("Expected File entry");
+        }
+    }
+
+    /// Sets the ID of this file. This has no effect if the archive keeps IDs synced.
+    pub fn set_id(&mut self, value: u16) {
+        if let IndexEntry::File { id, .. } = self.entry {
+            *id = value
+        } else {
+            panic!("Expected File entry");
+        }
+    }
+
+    /// Gets the size of this file, as it is stored in the archive.
+    pub fn size(&self) -> u32 {
+        if let IndexEntry::File { size, .. } = &self.entry {
+            *size
+        } else {
+            panic!("Expected File entry");
+        }
+    }
+
+    pub fn replace(&mut self, data: impl Into<FileData>) -> Result<()> {
+        let data = data.into();
+        let new_size = data_size(&data)?;
+
+        if let IndexEntry::File { contents, size, .. } = self.entry {
+            *contents = Contents::Replaced(data);
+            *size = new_size;
+            Ok(())
+        } else {
+            panic!("Expected File entry");
+        }
+    }
+
     pub fn attributes(&self) -> FileAttributes {
         if let IndexEntry::File { attributes, .. } = &self.entry {
             *attributes
@@ -89,6 +1095,19 @@ impl<'a> ArchivedFile<'a> {
         }
     }
 
+    /// Gets how the game loads this file, based on its attributes.
+    pub fn load_class(&self) -> Option<LoadClass> {
+        self.attributes().load_class()
+    }
+
+    pub fn set_load_class(&mut self, class: Option<LoadClass>) {
+        if let IndexEntry::File { attributes, .. } = self.entry {
+            attributes.set_load_class(class)
+        } else {
+            panic!("Expected File entry");
+        }
+    }
+
     pub fn set_attributes(&mut self, value: FileAttributes) {
         if let IndexEntry::File { attributes, .. } = self.entry {
             *attributes = value
@@ -98,53 +1117,252 @@ impl<'a> ArchivedFile<'a> {
     }
 }
 
-fn read_directory<T: ByteOrder>(
-    directory_index: usize,
-    directory_path: String,
-    directories: &[DirectoryNode],
-    files: &[FileNode],
-    table: &StringTable,
-    index: &mut BTreeMap<String, IndexEntry>,
-) -> Result<()> {
-    let directory = &directories[directory_index];
-    let file_start = T::read_u32(bytemuck::bytes_of(&directory.file_offset));
-    let file_count = T::read_u16(bytemuck::bytes_of(&directory.file_count));
+struct TreeReader<'a> {
+    directories: &'a [DirectoryNode],
+    files: &'a [FileNode],
+    table: &'a StringTable,
+    visited: Vec<bool>,
+    hash_check: HashCheck,
+    warnings: &'a mut Vec<RarcError>,
+    index: &'a mut BTreeMap<String, IndexEntry>,
+}
+
+impl<'a> TreeReader<'a> {
+    fn read_directory<T: ByteOrder>(
+        &mut self,
+        directory_index: usize,
+        position: u32,
+        directory_path: String,
+    ) -> Result<()> {
+        let directory = &self.directories[directory_index];
+        // Tags that match the generated one aren't kept, so they follow the name when the directory is renamed.
+        let tag =
+            (directory.tag != create_tag(file_name(&directory_path))).then_some(directory.tag);
+        let file_start = T::read_u32(bytemuck::bytes_of(&directory.file_offset));
+        let file_count = T::read_u16(bytemuck::bytes_of(&directory.file_count));
+
+        let name_offset = T::read_u32(bytemuck::bytes_of(&directory.name_offset));
+        if let Some(name) = self.table.string_at(name_offset as usize) {
+            let stored = T::read_u16(bytemuck::bytes_of(&directory.name_hash));
+            self.check_hash(&directory_path, name, stored)?;
+        }
+
+        for i in (file_start as usize)..(file_start as usize + file_count as usize) {
+            let file = self
+                .files
+                .get(i)
+                .ok_or(RarcError::MissingFile { index: i })?;
+
+            let name = match file.name::<T>(self.table) {
+                Some(x) => x,
+                None => continue,
+            };
+
+            let path = join_path(&directory_path, name);
+            let stored = T::read_u16(bytemuck::bytes_of(&file.name_hash));
+            self.check_hash(&path, name, stored)?;
+
+            if name == "." || name == ".." {
+                continue;
+            }
+            if !is_safe_name(name) {
+                return Err(RarcError::InvalidName { name: name.into() });
+            }
 
-    for i in (file_start as usize)..(file_start as usize + file_count as usize) {
-        let file = files.get(i).ok_or(RarcError::MissingFile { index: i })?;
+            if file.index == u16::MAX {
+                let directory = T::read_u32(bytemuck::bytes_of(&file.offset_or_index)) as usize;
+                match self.visited.get_mut(directory) {
+                    None => {
+                        return Err(RarcError::MissingDirectory {
+                            index: i,
+                            directory,
+                        })
+                    }
+                    Some(true) => return Err(RarcError::SharedDirectory { index: directory }),
+                    Some(visited) => *visited = true,
+                }
+
+                self.read_directory::<T>(directory, i as u32, path)?;
+            } else {
+                let offset = T::read_u32(bytemuck::bytes_of(&file.offset_or_index));
+                let size = T::read_u32(bytemuck::bytes_of(&file.size));
+                let attributes = FileAttributes::from_bits_truncate(file.attributes);
+                self.index.insert(
+                    path,
+                    IndexEntry::File {
+                        id: T::read_u16(bytemuck::bytes_of(&file.index)),
+                        contents: Contents::Archived {
+                            offset,
+                            compression: attributes.compression(),
+                        },
+                        size,
+                        attributes,
+                        position: i as u32,
+                        data_position: u32::MAX,
+                    },
+                );
+            }
+        }
+
+        self.index.insert(
+            directory_path,
+            IndexEntry::Directory {
+                position,
+                node: directory_index as u32,
+                tag,
+            },
+        );
+
+        Ok(())
+    }
 
-        let name = match file.name::<T>(table) {
-            Some(x) => x,
-            None => continue,
+    fn check_hash(&mut self, path: &str, name: &str, stored: u16) -> Result<()> {
+        let calculated = node::name_hash(name);
+        if stored == calculated {
+            return Ok(());
+        }
+
+        let error = RarcError::HashMismatch {
+            path: path.into(),
+            stored,
+            calculated,
         };
 
-        if name == "." || name == ".." {
-            continue;
+        match self.hash_check {
+            HashCheck::Strict => Err(error),
+            HashCheck::Lenient => {
+                self.warnings.push(error);
+                Ok(())
+            }
         }
+    }
+}
 
-        let mut path = String::with_capacity(directory_path.len() + name.len() + 1);
-        path.push_str(&directory_path);
-        path.push('/');
-        path.push_str(name);
+/// Gets the paths of all files in the order their data is written in.
+/// The data is grouped by load class, with files that have none at the end.
+/// Within a group, archived files keep their original order and replaced files follow.
+fn data_order(index: &BTreeMap<String, IndexEntry>) -> Vec<&str> {
+    let mut result = index
+        .iter()
+        .filter_map(|(path, entry)| match entry {
+            IndexEntry::File {
+                contents,
+                attributes,
+                data_position,
+                ..
+            } => {
+                let class = attributes.load_class().map_or(3, |x| x as u8);
+                let key = match contents {
+                    Contents::Archived { offset, .. } => (class, false, *offset),
+                    Contents::Replaced(_) => (class, true, *data_position),
+                };
+                Some((key, path.as_str()))
+            }
+            IndexEntry::Directory { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    result.sort();
+    result.into_iter().map(|(_, path)| path).collect()
+}
 
-        if file.index == u16::MAX {
-            let directory_index = T::read_u32(bytemuck::bytes_of(&file.offset_or_index)) as usize;
-            read_directory::<T>(directory_index, path, directories, files, table, index)?;
-        } else {
-            let offset = T::read_u32(bytemuck::bytes_of(&file.offset_or_index));
-            let size = T::read_u32(bytemuck::bytes_of(&file.size));
-            index.insert(
-                path,
-                IndexEntry::File {
-                    offset,
-                    size,
-                    attributes: FileAttributes::from_bits_truncate(file.attributes),
-                },
-            );
+fn open_contents<'a, F: Read + Seek>(
+    reader: &'a mut F,
+    data_offset: u64,
+    contents: &'a Contents,
+    size: u32,
+) -> Result<FileReader<'a, F>> {
+    let reader = match contents {
+        Contents::Archived { offset, .. } => FileReader::new(
+            Source::Archive(reader),
+            data_offset + *offset as u64,
+            size as u64,
+        )?,
+        Contents::Replaced(FileData::Memory(buffer)) => {
+            FileReader::new(Source::Memory(Cursor::new(buffer)), 0, size as u64)?
         }
+        Contents::Replaced(FileData::Path(path)) => {
+            FileReader::new(Source::File(File::open(path)?), 0, size as u64)?
+        }
+    };
+    Ok(reader)
+}
+
+fn read_all<F: Read + Seek>(mut reader: FileReader<'_, F>) -> Result<Vec<u8>> {
+    let mut result = Vec::with_capacity(reader.size() as usize);
+    reader.read_to_end(&mut result)?;
+
+    if result.len() as u64 != reader.size() {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(result)
+}
+
+fn normalize_path(path: &str) -> String {
+    let mut result = String::with_capacity(path.len() + 1);
+    for segment in path.split('/').filter(|x| !x.is_empty()) {
+        result.push('/');
+        result.push_str(segment);
+    }
+
+    if result.is_empty() {
+        result.push('/');
     }
 
-    index.insert(directory_path, IndexEntry::Directory { files: file_count });
+    result
+}
+
+fn check_name(name: &str) -> Result<()> {
+    if name.is_ascii() && is_safe_name(name) {
+        Ok(())
+    } else {
+        Err(RarcError::InvalidName { name: name.into() })
+    }
+}
+
+/// Whether a name stays a single component when it gets joined onto a path, so it can't escape its directory.
+pub(crate) fn is_safe_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+fn data_size(data: &FileData) -> Result<u32> {
+    let size = match data {
+        FileData::Memory(buffer) => buffer.len() as u64,
+        FileData::Path(path) => std::fs::metadata(path)?.len(),
+    };
+    u32::try_from(size).map_err(|_| RarcError::FileTooLarge)
+}
+
+fn join_path(directory: &str, name: &str) -> String {
+    let mut path = String::with_capacity(directory.len() + name.len() + 1);
+    path.push_str(directory);
+    if !directory.ends_with('/') {
+        path.push('/');
+    }
+    path.push_str(name);
+    path
+}
+
+fn parent_path(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(idx) => &path[..idx],
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(idx) => &path[(idx + 1)..],
+        None => path,
+    }
+}
+
+fn name_offset(offset: u32) -> Result<u16> {
+    u16::try_from(offset).map_err(|_| RarcError::StringTableTooLarge)
+}
 
+fn write_padding(mut writer: impl Write, size: usize) -> Result<()> {
+    const PADDING: [u8; 32] = [0; 32];
+    writer.write_all(&PADDING[..(align(size) - size)])?;
     Ok(())
 }
@@ -1,8 +1,10 @@
-use crate::{RarcError, Result};
-use byteorder::{ByteOrder, ReadBytesExt, WriteBytesExt};
+use crate::{Endian, RarcError, Result};
+use byteorder::{BigEndian, ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::io::{Read, Seek, SeekFrom, Write};
 
 pub struct RarcHeader {
+    /// Where the archive starts in the stream it was read from.
+    pub base: u64,
     pub size: u32,
     pub data_offset: u32,
     pub data_length: u32,
@@ -23,11 +25,37 @@ pub struct RarcHeader {
 const RARC_MAGIC: &[u8; 4] = b"RARC";
 
 impl RarcHeader {
+    /// Detects the byte order from the offset of the data header, which is always `0x20`.
+    /// The reader is moved back to where the archive starts afterwards.
+    pub fn detect(mut reader: impl Read + Seek) -> Result<Endian> {
+        let base = reader.stream_position()?;
+        let mut buffer = [0; 0x0C];
+        reader.read_exact(&mut buffer)?;
+        reader.seek(SeekFrom::Start(base))?;
+
+        if &buffer[..4] != RARC_MAGIC {
+            return Err(RarcError::InvaildMagic);
+        }
+
+        if BigEndian::read_u32(&buffer[8..]) == 0x20 {
+            Ok(Endian::Big)
+        } else if LittleEndian::read_u32(&buffer[8..]) == 0x20 {
+            Ok(Endian::Little)
+        } else {
+            Err(RarcError::UnknownByteOrder)
+        }
+    }
+
+    /// Reads the header of an archive that starts at the current position of the reader.
     pub fn read<T: ByteOrder>(mut reader: impl Read + Seek) -> Result<Self> {
+        let base = reader.stream_position()?;
+        let length = reader.seek(SeekFrom::End(0))?.saturating_sub(base);
+        reader.seek(SeekFrom::Start(base))?;
+
         let mut magic = [0; 4];
         reader.read_exact(&mut magic)?;
 
-        if &magic == RARC_MAGIC {
+        if &magic != RARC_MAGIC {
             return Err(RarcError::InvaildMagic);
         }
 
@@ -39,11 +67,49 @@ impl RarcHeader {
         let aram = reader.read_u32::<T>()?;
         let dvd = reader.read_u32::<T>()?;
 
+        check_bounds("size", 0x04, size, 0, size as u64, length)?;
+        check_bounds(
+            "data_header",
+            0x08,
+            data_header,
+            data_header as u64,
+            0x20,
+            length,
+        )?;
+        check_bounds(
+            "data_length",
+            0x10,
+            data_length,
+            data_offset as u64 + 0x20,
+            data_length as u64,
+            length,
+        )?;
+
+        // The data section starts with the MRAM files, followed by the ARAM and DVD files.
+        let mut end = 0;
+        for (field, offset, value) in [
+            ("mram", 0x14, mram),
+            ("aram", 0x18, aram),
+            ("dvd", 0x1C, dvd),
+        ] {
+            end += value as u64;
+            if end > data_length as u64 {
+                return Err(RarcError::LoadSizeOutOfBounds {
+                    field,
+                    offset,
+                    value,
+                    end,
+                    length: data_length,
+                });
+            }
+        }
+
         if data_header != 0x20 {
-            reader.seek(SeekFrom::Start(data_header as u64))?;
+            reader.seek(SeekFrom::Start(base + data_header as u64))?;
         }
 
-        Ok(RarcHeader {
+        let header = RarcHeader {
+            base,
             size,
             data_offset,
             data_length,
@@ -59,7 +125,49 @@ impl RarcHeader {
             string_offset: reader.read_u32::<T>()?,
             next_file: reader.read_u16::<T>()?,
             keep_synced: reader.read_u8()? == 1,
-        })
+        };
+
+        let data_header = data_header as u64;
+        check_bounds(
+            "directory_nodes",
+            data_header,
+            header.directory_nodes,
+            header.directory_offset as u64 + 0x20,
+            header.directory_nodes as u64 * 0x10,
+            length,
+        )?;
+        check_bounds(
+            "file_nodes",
+            data_header + 0x08,
+            header.file_nodes,
+            header.file_offset as u64 + 0x20,
+            header.file_nodes as u64 * 0x14,
+            length,
+        )?;
+        check_bounds(
+            "string_size",
+            data_header + 0x10,
+            header.string_size,
+            header.string_offset as u64 + 0x20,
+            header.string_size as u64,
+            length,
+        )?;
+
+        if header.directory_nodes == 0 && header.file_nodes != 0 {
+            return Err(RarcError::HeaderMismatch {
+                field: "file_nodes",
+                offset: data_header + 0x08,
+                value: header.file_nodes,
+                expected: 0,
+            });
+        }
+
+        Ok(header)
+    }
+
+    /// Gets the position in the stream of an offset that is relative to the data header.
+    pub fn position(&self, offset: u32) -> u64 {
+        self.base + offset as u64 + 0x20
     }
 
     pub fn write<T: ByteOrder>(&self, mut writer: impl Write) -> Result<()> {
@@ -84,3 +192,25 @@ impl RarcHeader {
         Ok(())
     }
 }
+
+fn check_bounds(
+    field: &'static str,
+    offset: u64,
+    value: u32,
+    start: u64,
+    size: u64,
+    length: u64,
+) -> Result<()> {
+    let end = start + size;
+    if end > length {
+        Err(RarcError::HeaderOutOfBounds {
+            field,
+            offset,
+            value,
+            end,
+            length,
+        })
+    } else {
+        Ok(())
+    }
+}
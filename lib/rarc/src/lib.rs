@@ -1,25 +1,132 @@
 mod archive;
 mod header;
+#[cfg(feature = "manifest")]
+mod manifest;
 mod node;
+mod reader;
 mod string_table;
+mod validate;
+mod view;
 
-pub use archive::Archive;
-pub use node::FileAttributes;
+pub use archive::{Archive, ArchivedFile, EntryKind, FileData, HashCheck, ReadOptions};
+pub use endian::Endian;
+#[cfg(feature = "manifest")]
+pub use manifest::MANIFEST_NAME;
+pub use node::{create_tag, name_hash, DirectoryNode, FileAttributes, FileNode, LoadClass};
+pub use reader::{ArchiveReader, FileReader};
+pub use validate::validate;
+pub use view::{ArchiveView, Files};
 
 use thiserror::Error;
 
+pub mod byteorder {
+    pub use byteorder::{BigEndian, ByteOrder, LittleEndian, NativeEndian};
+}
+
+pub mod compression {
+    pub use yaz0::{CompressionLevel, Format};
+}
+
 #[derive(Debug, Error)]
 pub enum RarcError {
     #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
+    Yaz0Error(#[from] yaz0::Yaz0Error),
+    #[error(transparent)]
     PodCastError(#[from] bytemuck::PodCastError),
     #[error("Rarc file has an invaild magic number")]
     InvaildMagic,
+    #[error("The byte order can't be detected, the data header offset isn't 0x20 in either order")]
+    UnknownByteOrder,
     #[error("The first directory node in not the root directory")]
     FirstDirectoryNotRoot,
     #[error("A file in a directory is missing. Expected Offset: {index}")]
     MissingFile { index: usize },
+    #[error("The header field {field} at {offset:#x} with value {value:#x} makes its section end at {end:#x}, past the end of the {length:#x} byte stream")]
+    HeaderOutOfBounds {
+        field: &'static str,
+        offset: u64,
+        value: u32,
+        end: u64,
+        length: u64,
+    },
+    #[error("The header field {field} at {offset:#x} with value {value:#x} makes the load classes end at {end:#x}, past the end of the {length:#x} byte data section")]
+    LoadSizeOutOfBounds {
+        field: &'static str,
+        offset: u64,
+        value: u32,
+        end: u64,
+        length: u32,
+    },
+    #[error("The header field {field} at {offset:#x} has the value {value:#x}, but {expected:#x} was expected")]
+    HeaderMismatch {
+        field: &'static str,
+        offset: u64,
+        value: u32,
+        expected: u32,
+    },
+    #[error("The {node} node {index} has the name offset {name_offset:#x}, which is outside of the {size:#x} byte string table")]
+    NameOutOfRange {
+        node: &'static str,
+        index: usize,
+        name_offset: u32,
+        size: u32,
+    },
+    #[error("The name hash of {path} is {stored:#06x}, but {calculated:#06x} was expected")]
+    HashMismatch {
+        path: String,
+        stored: u16,
+        calculated: u16,
+    },
+    #[error(
+        "The file node {index} references the directory node {directory}, which doesn't exist"
+    )]
+    MissingDirectory { index: usize, directory: usize },
+    #[error("The directory node {index} is referenced from more than one place in the tree")]
+    SharedDirectory { index: usize },
+    #[error("The file {name} (node {index}) spans {start:#x} to {end:#x}, past the end of the {length:#x} byte data section")]
+    FileOutOfBounds {
+        name: String,
+        index: usize,
+        start: u64,
+        end: u64,
+        length: u32,
+    },
+    #[error("The files {first} (node {first_index}) and {second} (node {second_index}) overlap in the data section")]
+    OverlappingFiles {
+        first: String,
+        first_index: usize,
+        second: String,
+        second_index: usize,
+    },
+    #[error("The string table is too large to be addressed by the file nodes")]
+    StringTableTooLarge,
+    #[error("A file is larger than 4 GiB and can't be stored in an archive")]
+    FileTooLarge,
+    #[error("The archive can't hold more than 65535 files")]
+    TooManyFiles,
+    #[error("Nothing exists at {path}")]
+    NotFound { path: String },
+    #[error("An entry already exists at {path}")]
+    AlreadyExists { path: String },
+    #[error("{path} is not a directory")]
+    NotADirectory { path: String },
+    #[error("{path} is a directory")]
+    IsADirectory { path: String },
+    #[error("The directory {path} can't be moved into itself")]
+    MoveIntoItself { path: String },
+    #[cfg(feature = "manifest")]
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(String),
+    #[error("The root directory can't be removed, moved or retagged")]
+    RootDirectory,
+    #[error("{name:?} is not a vaild name, names have to be ASCII, can't contain slashes and can't be empty, \".\" or \"..\"")]
+    InvalidName { name: String },
 }
 
 type Result<T> = std::result::Result<T, RarcError>;
+
+pub(crate) fn align(value: usize) -> usize {
+    (value + 31) & !31
+}
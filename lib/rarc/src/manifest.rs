@@ -0,0 +1,269 @@
+use crate::archive::{Archive, EntryKind};
+use crate::node::FileAttributes;
+use crate::{RarcError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
+use yaz0::Format;
+
+/// The name of the manifest that is written next to the extracted contents of an archive.
+pub const MANIFEST_NAME: &str = ".rarc.toml";
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    root: String,
+    keep_synced: bool,
+    next_file: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compression: Option<String>,
+    #[serde(default, rename = "entry")]
+    entries: Vec<ManifestEntry>,
+}
+
+/// An entry of the manifest, which are listed in the order their nodes were stored in.
+#[derive(Serialize, Deserialize)]
+struct ManifestEntry {
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    attributes: Option<u8>,
+    /// The position of the file data in the data section.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<u32>,
+}
+
+impl<F: Read + Seek> Archive<F> {
+    /// Extracts every file of the archive into the given directory, creating it if needed.
+    /// Compressed files are extracted decompressed. The root name, file IDs, attributes,
+    /// directory tags, the order of all entries and of the file data are written to a [`MANIFEST_NAME`] file,
+    /// so [`Archive::from_directory`] can pack the directory into the same archive again.
+    pub fn extract_to(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let target = path.as_ref();
+        fs::create_dir_all(target)?;
+
+        let entries = self
+            .ordered_entries()
+            .into_iter()
+            .map(|(path, kind)| (path.to_string(), kind))
+            .collect::<Vec<_>>();
+        let data_positions = self
+            .data_order()
+            .into_iter()
+            .enumerate()
+            .map(|(position, path)| (path.to_string(), position as u32))
+            .collect::<HashMap<_, _>>();
+
+        let mut manifest = Manifest {
+            root: self.root_name().into(),
+            keep_synced: self.keep_synced(),
+            next_file: self.next_file_id(),
+            compression: self.compression().map(format_name),
+            entries: Vec::with_capacity(entries.len()),
+        };
+
+        for (path, kind) in entries {
+            // Names are checked when they are read, but the output must never leave the target either way.
+            let relative = Path::new(&path[1..]);
+            if !relative
+                .components()
+                .all(|x| matches!(x, Component::Normal(_)))
+            {
+                return Err(RarcError::InvalidName { name: path });
+            }
+            let output = target.join(relative);
+
+            let entry = match kind {
+                EntryKind::Directory => {
+                    fs::create_dir_all(&output)?;
+                    let tag = self.custom_directory_tag(&path);
+                    ManifestEntry {
+                        path: path[1..].into(),
+                        tag: tag.map(|x| String::from_utf8_lossy(&x).into()),
+                        id: None,
+                        attributes: None,
+                        data: None,
+                    }
+                }
+                EntryKind::File => {
+                    let mut file = self
+                        .open_file(&path)
+                        .ok_or(RarcError::NotFound { path: path.clone() })?;
+
+                    let mut writer = BufWriter::new(File::create(&output)?);
+                    io::copy(&mut file.reader()?, &mut writer)?;
+                    writer.flush()?;
+
+                    ManifestEntry {
+                        path: path[1..].into(),
+                        tag: None,
+                        id: Some(file.id()),
+                        attributes: Some(file.attributes().bits()),
+                        data: data_positions.get(&path).copied(),
+                    }
+                }
+            };
+            manifest.entries.push(entry);
+        }
+
+        let manifest =
+            toml::to_string(&manifest).map_err(|e| RarcError::InvalidManifest(e.to_string()))?;
+        fs::write(target.join(MANIFEST_NAME), manifest)?;
+        Ok(())
+    }
+}
+
+impl Archive<io::Empty> {
+    /// Packs the contents of a directory into a new archive, whose files are read when it is written.
+    /// If the directory contains a [`MANIFEST_NAME`] file, like the ones written by [`Archive::extract_to`],
+    /// the settings and entries listed in it are restored. Files that aren't listed are added after
+    /// the listed ones, while listed entries that don't exist anymore are left out.
+    pub fn from_directory(path: impl AsRef<Path>) -> Result<Self> {
+        let source = path.as_ref();
+
+        let manifest = match fs::read_to_string(source.join(MANIFEST_NAME)) {
+            Ok(text) => Some(
+                toml::from_str::<Manifest>(&text)
+                    .map_err(|e| RarcError::InvalidManifest(e.message().to_string()))?,
+            ),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let root = match &manifest {
+            Some(manifest) => manifest.root.clone(),
+            None => source
+                .file_name()
+                .and_then(|x| x.to_str())
+                .unwrap_or("root")
+                .to_string(),
+        };
+
+        let mut archive = Archive::new(&root)?;
+        archive.create_directory("/")?;
+        add_directory(&mut archive, source, String::new())?;
+
+        let manifest = match manifest {
+            Some(x) => x,
+            None => return Ok(archive),
+        };
+
+        archive.set_keep_synced(manifest.keep_synced);
+        archive.set_compression(match manifest.compression.as_deref() {
+            None => None,
+            Some(name) => Some(parse_format(name)?),
+        });
+
+        let mut listed = HashSet::new();
+        let mut next_file = manifest.next_file;
+        for (position, entry) in manifest.entries.into_iter().enumerate() {
+            let path = format!("/{}", entry.path.trim_matches('/'));
+            if !archive.contains(&path) {
+                continue;
+            }
+
+            archive.set_position(&path, position as u32);
+            if let Some(data) = entry.data {
+                archive.set_data_position(&path, data);
+            }
+
+            if let Some(tag) = entry.tag {
+                archive.set_directory_tag(&path, Some(parse_tag(&tag)?))?;
+            }
+
+            if let Some(mut file) = archive.open_file(&path) {
+                if let Some(id) = entry.id {
+                    file.set_id(id);
+                    next_file = next_file.max(id.saturating_add(1));
+                }
+                if let Some(attributes) = entry.attributes {
+                    file.set_attributes(FileAttributes::from_bits_truncate(attributes));
+                }
+            }
+
+            listed.insert(path);
+        }
+
+        // Files that weren't listed get numbered after the listed ones.
+        let unlisted = archive
+            .ordered_entries()
+            .into_iter()
+            .filter(|(path, kind)| *kind == EntryKind::File && !listed.contains(*path))
+            .map(|(path, _)| path.to_string())
+            .collect::<Vec<_>>();
+        for path in unlisted {
+            if next_file == u16::MAX {
+                return Err(RarcError::TooManyFiles);
+            }
+            if let Some(mut file) = archive.open_file(&path) {
+                file.set_id(next_file);
+                next_file += 1;
+            }
+        }
+        archive.set_next_file_id(next_file);
+
+        Ok(archive)
+    }
+}
+
+fn add_directory(archive: &mut Archive<io::Empty>, source: &Path, prefix: String) -> Result<()> {
+    let mut entries = fs::read_dir(source)?
+        .map(|entry| entry.map(|x| x.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    entries.sort();
+
+    for entry in entries {
+        let name =
+            entry
+                .file_name()
+                .and_then(|x| x.to_str())
+                .ok_or_else(|| RarcError::InvalidName {
+                    name: entry
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .into(),
+                })?;
+
+        if prefix.is_empty() && name == MANIFEST_NAME {
+            continue;
+        }
+
+        let path = format!("{prefix}/{name}");
+        if entry.is_dir() {
+            archive.create_directory(&path)?;
+            add_directory(archive, &entry, path)?;
+        } else {
+            archive.create_file(&path, entry)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn format_name(format: Format) -> String {
+    match format {
+        Format::Yaz0 => "yaz0".into(),
+        Format::Yay0 => "yay0".into(),
+    }
+}
+
+fn parse_format(name: &str) -> Result<Format> {
+    match name {
+        "yaz0" => Ok(Format::Yaz0),
+        "yay0" => Ok(Format::Yay0),
+        _ => Err(RarcError::InvalidManifest(format!(
+            "unknown compression format `{name}`"
+        ))),
+    }
+}
+
+fn parse_tag(tag: &str) -> Result<[u8; 4]> {
+    tag.as_bytes()
+        .try_into()
+        .map_err(|_| RarcError::InvalidManifest(format!("the tag `{tag}` isn't 4 bytes long")))
+}
@@ -1,10 +1,12 @@
 use crate::header::RarcHeader;
 use crate::string_table::StringTable;
-use crate::{RarcError, Result};
+use crate::Result;
 use bitflags::bitflags;
 use bytemuck::{Pod, Zeroable};
 use byteorder::ByteOrder;
 use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use yaz0::Format;
 
 #[derive(Clone, Copy, Zeroable, Pod)]
 #[repr(C)]
@@ -16,16 +18,41 @@ pub struct DirectoryNode {
     pub file_offset: u32,
 }
 
+impl DirectoryNode {
+    pub fn new<T: ByteOrder>(
+        tag: [u8; 4],
+        name_offset: u32,
+        name_hash: u16,
+        file_count: u16,
+        file_offset: u32,
+    ) -> Self {
+        DirectoryNode {
+            tag,
+            name_offset: encode_u32::<T>(name_offset),
+            name_hash: encode_u16::<T>(name_hash),
+            file_count: encode_u16::<T>(file_count),
+            file_offset: encode_u32::<T>(file_offset),
+        }
+    }
+
+    /// Gets the range of file nodes that belong to this directory.
+    pub fn file_range<T: ByteOrder>(&self) -> Range<usize> {
+        let start = T::read_u32(bytemuck::bytes_of(&self.file_offset)) as usize;
+        let count = T::read_u16(bytemuck::bytes_of(&self.file_count)) as usize;
+        start..(start + count)
+    }
+}
+
 pub fn read_directory_nodes(
     mut reader: impl Read + Seek,
     header: &RarcHeader,
 ) -> Result<Vec<DirectoryNode>> {
-    reader.seek(SeekFrom::Start(header.directory_offset as u64 + 0x20))?;
+    reader.seek(SeekFrom::Start(header.position(header.directory_offset)))?;
 
-    let mut result = vec![0; header.directory_nodes as usize * 0x10];
-    reader.read_exact(&mut result)?;
+    let mut result = vec![DirectoryNode::zeroed(); header.directory_nodes as usize];
+    reader.read_exact(bytemuck::cast_slice_mut(&mut result))?;
 
-    bytemuck::allocation::try_cast_vec(result).map_err(|(e, _)| RarcError::PodCastError(e))
+    Ok(result)
 }
 
 bitflags! {
@@ -40,6 +67,73 @@ bitflags! {
     }
 }
 
+impl FileAttributes {
+    /// Gets the compression format a file with these attributes is stored with.
+    /// Files are only compressed if they are marked as [`COMPRESSED`](FileAttributes::COMPRESSED),
+    /// in which case [`YAZ0_COMPRESSED`](FileAttributes::YAZ0_COMPRESSED) selects Yaz0 over Yay0.
+    pub fn compression(self) -> Option<Format> {
+        if !self.contains(FileAttributes::COMPRESSED) {
+            None
+        } else if self.contains(FileAttributes::YAZ0_COMPRESSED) {
+            Some(Format::Yaz0)
+        } else {
+            Some(Format::Yay0)
+        }
+    }
+
+    /// Sets the compression flags to store a file with the given compression format.
+    pub fn set_compression(&mut self, format: Option<Format>) {
+        self.remove(FileAttributes::COMPRESSED | FileAttributes::YAZ0_COMPRESSED);
+        match format {
+            Some(Format::Yaz0) => {
+                self.insert(FileAttributes::COMPRESSED | FileAttributes::YAZ0_COMPRESSED)
+            }
+            Some(Format::Yay0) => self.insert(FileAttributes::COMPRESSED),
+            None => {}
+        }
+    }
+
+    /// Gets how the game loads a file with these attributes.
+    /// If several load flags are set, the first class in the order of [`LoadClass`] wins.
+    pub fn load_class(self) -> Option<LoadClass> {
+        if self.contains(FileAttributes::PRELOAD_TO_MRAM) {
+            Some(LoadClass::Mram)
+        } else if self.contains(FileAttributes::PRELOAD_TO_ARAM) {
+            Some(LoadClass::Aram)
+        } else if self.contains(FileAttributes::LOAD_FROM_DVD) {
+            Some(LoadClass::Dvd)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the load flags, so a file with these attributes gets loaded the given way.
+    pub fn set_load_class(&mut self, class: Option<LoadClass>) {
+        self.remove(
+            FileAttributes::PRELOAD_TO_MRAM
+                | FileAttributes::PRELOAD_TO_ARAM
+                | FileAttributes::LOAD_FROM_DVD,
+        );
+        match class {
+            Some(LoadClass::Mram) => self.insert(FileAttributes::PRELOAD_TO_MRAM),
+            Some(LoadClass::Aram) => self.insert(FileAttributes::PRELOAD_TO_ARAM),
+            Some(LoadClass::Dvd) => self.insert(FileAttributes::LOAD_FROM_DVD),
+            None => {}
+        }
+    }
+}
+
+/// How the game loads a file, in the order the data section is grouped by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadClass {
+    /// Preloaded into main memory when the archive is mounted.
+    Mram,
+    /// Preloaded into auxiliary memory when the archive is mounted.
+    Aram,
+    /// Read from the disc when the file is needed.
+    Dvd,
+}
+
 #[derive(Clone, Copy, Zeroable, Pod)]
 #[repr(C)]
 pub struct FileNode {
@@ -50,20 +144,98 @@ pub struct FileNode {
     pub name_offset: u16,
     pub offset_or_index: u32,
     pub size: u32,
+    pub data: u32,
 }
 
 impl FileNode {
+    pub fn new<T: ByteOrder>(
+        index: u16,
+        name_hash: u16,
+        attributes: FileAttributes,
+        name_offset: u16,
+        offset_or_index: u32,
+        size: u32,
+    ) -> Self {
+        FileNode {
+            index: encode_u16::<T>(index),
+            name_hash: encode_u16::<T>(name_hash),
+            attributes: attributes.bits(),
+            padding: 0,
+            name_offset: encode_u16::<T>(name_offset),
+            offset_or_index: encode_u32::<T>(offset_or_index),
+            size: encode_u32::<T>(size),
+            data: 0,
+        }
+    }
+
     pub fn name<'a, T: ByteOrder>(&self, table: &'a StringTable) -> Option<&'a str> {
         let offset = T::read_u16(bytemuck::bytes_of(&self.name_offset));
         table.string_at(offset as usize)
     }
+
+    pub fn id<T: ByteOrder>(&self) -> u16 {
+        T::read_u16(bytemuck::bytes_of(&self.index))
+    }
+
+    pub fn file_attributes(&self) -> FileAttributes {
+        FileAttributes::from_bits_truncate(self.attributes)
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.index == u16::MAX
+    }
+
+    /// Gets the index of the directory node this node refers to, if it is a directory.
+    pub fn directory_index<T: ByteOrder>(&self) -> Option<usize> {
+        self.is_directory()
+            .then(|| T::read_u32(bytemuck::bytes_of(&self.offset_or_index)) as usize)
+    }
+
+    /// Gets the range of the data section this node's contents are stored in, if it is a file.
+    pub fn data_range<T: ByteOrder>(&self) -> Option<Range<usize>> {
+        if self.is_directory() {
+            return None;
+        }
+
+        let start = T::read_u32(bytemuck::bytes_of(&self.offset_or_index)) as usize;
+        let size = T::read_u32(bytemuck::bytes_of(&self.size)) as usize;
+        Some(start..(start + size))
+    }
 }
 
 pub fn read_file_nodes(mut reader: impl Read + Seek, header: &RarcHeader) -> Result<Vec<FileNode>> {
-    reader.seek(SeekFrom::Start(header.file_offset as u64 + 0x20))?;
+    reader.seek(SeekFrom::Start(header.position(header.file_offset)))?;
 
-    let mut result = vec![0; header.file_nodes as usize * 0x10];
-    reader.read_exact(&mut result)?;
+    let mut result = vec![FileNode::zeroed(); header.file_nodes as usize];
+    reader.read_exact(bytemuck::cast_slice_mut(&mut result))?;
+
+    Ok(result)
+}
+
+/// Calculates the hash that is stored next to the name of every directory and file node.
+pub fn name_hash(name: &str) -> u16 {
+    name.bytes().fold(0u16, |result, c| {
+        result.wrapping_mul(3).wrapping_add(c as u16)
+    })
+}
+
+/// Creates a directory tag from the first 4 characters of a name, in uppercase and padded with spaces.
+pub fn create_tag(name: &str) -> [u8; 4] {
+    let mut result = [b' '; 4];
+    for (target, c) in result.iter_mut().zip(name.bytes()) {
+        *target = c.to_ascii_uppercase();
+    }
+    result
+}
+
+fn encode_u16<T: ByteOrder>(value: u16) -> u16 {
+    let mut result = 0;
+    T::write_u16(bytemuck::bytes_of_mut(&mut result), value);
+    result
+}
 
-    bytemuck::allocation::try_cast_vec(result).map_err(|(e, _)| RarcError::PodCastError(e))
+fn encode_u32<T: ByteOrder>(value: u32) -> u32 {
+    let mut result = 0;
+    T::write_u32(bytemuck::bytes_of_mut(&mut result), value);
+    result
 }
@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Seek, SeekFrom};
+
+/// The reader behind an archive opened with [`Archive::open`](crate::Archive::open).
+pub enum ArchiveReader {
+    /// An uncompressed archive that is read directly from its file.
+    File(BufReader<File>),
+    /// A compressed archive that was decompressed into memory.
+    Memory(Cursor<Vec<u8>>),
+    /// An uncompressed archive that is mapped into memory.
+    #[cfg(feature = "mmap")]
+    Mapped(Cursor<memmap2::Mmap>),
+}
+
+impl ArchiveReader {
+    pub(crate) fn as_slice(&self) -> Option<&[u8]> {
+        match self {
+            ArchiveReader::File(_) => None,
+            ArchiveReader::Memory(reader) => Some(reader.get_ref()),
+            #[cfg(feature = "mmap")]
+            ArchiveReader::Mapped(reader) => Some(reader.get_ref()),
+        }
+    }
+}
+
+impl Read for ArchiveReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            ArchiveReader::File(reader) => reader.read(buf),
+            ArchiveReader::Memory(reader) => reader.read(buf),
+            #[cfg(feature = "mmap")]
+            ArchiveReader::Mapped(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl Seek for ArchiveReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            ArchiveReader::File(reader) => reader.seek(pos),
+            ArchiveReader::Memory(reader) => reader.seek(pos),
+            #[cfg(feature = "mmap")]
+            ArchiveReader::Mapped(reader) => reader.seek(pos),
+        }
+    }
+}
+
+pub(crate) enum Source<'a, F> {
+    Archive(&'a mut F),
+    Memory(Cursor<&'a [u8]>),
+    Owned(Cursor<Vec<u8>>),
+    File(File),
+}
+
+/// A bounded view over the contents of a single file in an archive.
+/// Reading and seeking is relative to the start of the file and never goes past its end.
+pub struct FileReader<'a, F: Read + Seek> {
+    source: Source<'a, F>,
+    start: u64,
+    size: u64,
+    position: u64,
+}
+
+impl<'a, F: Read + Seek> FileReader<'a, F> {
+    pub(crate) fn new(mut source: Source<'a, F>, start: u64, size: u64) -> io::Result<Self> {
+        source.seek(SeekFrom::Start(start))?;
+        Ok(FileReader {
+            source,
+            start,
+            size,
+            position: 0,
+        })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl<'a, F: Read + Seek> Read for FileReader<'a, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.size.saturating_sub(self.position);
+        let len = remaining.min(buf.len() as u64) as usize;
+        if len == 0 {
+            return Ok(0);
+        }
+
+        let read = self.source.read(&mut buf[..len])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl<'a, F: Read + Seek> Seek for FileReader<'a, F> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        let position = position.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+
+        self.source.seek(SeekFrom::Start(self.start + position))?;
+        self.position = position;
+        Ok(position)
+    }
+}
+
+impl<'a, F: Read + Seek> Read for Source<'a, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Source::Archive(reader) => reader.read(buf),
+            Source::Memory(reader) => reader.read(buf),
+            Source::Owned(reader) => reader.read(buf),
+            Source::File(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl<'a, F: Read + Seek> Seek for Source<'a, F> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Source::Archive(reader) => reader.seek(pos),
+            Source::Memory(reader) => reader.seek(pos),
+            Source::Owned(reader) => reader.seek(pos),
+            Source::File(reader) => reader.seek(pos),
+        }
+    }
+}
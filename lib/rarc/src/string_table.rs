@@ -1,5 +1,6 @@
 use crate::header::RarcHeader;
-use crate::Result;
+use crate::{align, Result};
+use std::collections::HashMap;
 use std::io::{Read, Seek, SeekFrom};
 
 pub struct StringTable {
@@ -8,7 +9,7 @@ pub struct StringTable {
 
 impl StringTable {
     pub fn read(mut reader: impl Read + Seek, header: &RarcHeader) -> Result<Self> {
-        reader.seek(SeekFrom::Start(header.string_offset as u64 + 0x20))?;
+        reader.seek(SeekFrom::Start(header.position(header.string_offset)))?;
 
         let mut buffer = vec![0; header.string_size as usize];
         reader.read_exact(&mut buffer)?;
@@ -17,15 +18,46 @@ impl StringTable {
     }
 
     pub fn string_at(&self, start: usize) -> Option<&str> {
-        let buffer = match self.buffer[start..].iter().position(|x| *x == 0) {
-            Some(end) => &self.buffer[start..end],
-            None => &self.buffer[start..],
-        };
-
-        if buffer.is_ascii() {
-            Some(unsafe { std::str::from_utf8_unchecked(buffer) })
-        } else {
-            None
+        string_at(&self.buffer, start)
+    }
+}
+
+/// Gets the null terminated ASCII string starting at the given offset of a string table.
+pub(crate) fn string_at(buffer: &[u8], start: usize) -> Option<&str> {
+    let buffer = buffer.get(start..)?;
+    let buffer = match buffer.iter().position(|x| *x == 0) {
+        Some(end) => &buffer[..end],
+        None => buffer,
+    };
+
+    if buffer.is_ascii() {
+        Some(unsafe { std::str::from_utf8_unchecked(buffer) })
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct StringTableWriter {
+    buffer: Vec<u8>,
+    offsets: HashMap<String, u32>,
+}
+
+impl StringTableWriter {
+    pub fn write_str(&mut self, value: &str) -> u32 {
+        if let Some(offset) = self.offsets.get(value) {
+            return *offset;
         }
+
+        let offset = self.buffer.len() as u32;
+        self.buffer.extend_from_slice(value.as_bytes());
+        self.buffer.push(0);
+        self.offsets.insert(value.into(), offset);
+        offset
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.buffer.resize(align(self.buffer.len()), 0);
+        self.buffer
     }
 }
@@ -0,0 +1,206 @@
+use crate::header::RarcHeader;
+use crate::node::{self, DirectoryNode, FileNode};
+use crate::string_table::StringTable;
+use crate::{RarcError, Result};
+use byteorder::ByteOrder;
+use std::io::{Read, Seek};
+
+/// Checks the structure of an uncompressed archive, without building an index of it.
+/// Besides the header, this checks the name offsets and hashes of every node, the directory references for nodes that are shared or cyclic
+/// and the file nodes for files that lie outside of the data section or overlap each other.
+/// Every problem that was found gets returned, so an empty vec means the archive is vaild.
+pub fn validate<T: ByteOrder>(mut reader: impl Read + Seek) -> Vec<RarcError> {
+    let (header, table, directories, files) = match read_nodes::<T>(&mut reader) {
+        Ok(x) => x,
+        Err(e) => return vec![e],
+    };
+
+    let mut errors = Vec::new();
+    check_names::<T>(&header, &directories, &files, &mut errors);
+    check_hashes::<T>(&directories, &files, &table, &mut errors);
+    check_directories::<T>(&directories, &files, &table, &mut errors);
+    check_data::<T>(&header, &files, &table, &mut errors);
+    errors
+}
+
+type Nodes = (RarcHeader, StringTable, Vec<DirectoryNode>, Vec<FileNode>);
+
+fn read_nodes<T: ByteOrder>(mut reader: impl Read + Seek) -> Result<Nodes> {
+    let header = RarcHeader::read::<T>(&mut reader)?;
+    let table = StringTable::read(&mut reader, &header)?;
+    let directories = node::read_directory_nodes(&mut reader, &header)?;
+    let files = node::read_file_nodes(&mut reader, &header)?;
+
+    if directories.first().is_some_and(|x| &x.tag != b"ROOT") {
+        return Err(RarcError::FirstDirectoryNotRoot);
+    }
+
+    Ok((header, table, directories, files))
+}
+
+fn check_names<T: ByteOrder>(
+    header: &RarcHeader,
+    directories: &[DirectoryNode],
+    files: &[FileNode],
+    errors: &mut Vec<RarcError>,
+) {
+    let directory_names = directories
+        .iter()
+        .map(|x| T::read_u32(bytemuck::bytes_of(&x.name_offset)));
+    let file_names = files
+        .iter()
+        .map(|x| T::read_u16(bytemuck::bytes_of(&x.name_offset)) as u32);
+
+    let nodes = directory_names
+        .enumerate()
+        .map(|(i, x)| ("directory", i, x))
+        .chain(file_names.enumerate().map(|(i, x)| ("file", i, x)));
+
+    for (node, index, name_offset) in nodes {
+        if name_offset >= header.string_size {
+            errors.push(RarcError::NameOutOfRange {
+                node,
+                index,
+                name_offset,
+                size: header.string_size,
+            });
+        }
+    }
+}
+
+fn check_hashes<T: ByteOrder>(
+    directories: &[DirectoryNode],
+    files: &[FileNode],
+    table: &StringTable,
+    errors: &mut Vec<RarcError>,
+) {
+    let directory_names = directories.iter().enumerate().map(|(i, x)| {
+        let name_offset = T::read_u32(bytemuck::bytes_of(&x.name_offset));
+        let hash = T::read_u16(bytemuck::bytes_of(&x.name_hash));
+        (
+            format!("directory node {i}"),
+            table.string_at(name_offset as usize),
+            hash,
+        )
+    });
+    let file_names = files.iter().enumerate().map(|(i, x)| {
+        let hash = T::read_u16(bytemuck::bytes_of(&x.name_hash));
+        (format!("file node {i}"), x.name::<T>(table), hash)
+    });
+
+    for (node, name, stored) in directory_names.chain(file_names) {
+        let name = match name {
+            Some(x) => x,
+            None => continue,
+        };
+
+        let calculated = node::name_hash(name);
+        if stored != calculated {
+            errors.push(RarcError::HashMismatch {
+                path: format!("{name} ({node})"),
+                stored,
+                calculated,
+            });
+        }
+    }
+}
+
+fn check_directories<T: ByteOrder>(
+    directories: &[DirectoryNode],
+    files: &[FileNode],
+    table: &StringTable,
+    errors: &mut Vec<RarcError>,
+) {
+    if directories.is_empty() {
+        return;
+    }
+
+    let mut visited = vec![false; directories.len()];
+    let mut pending = vec![0];
+    visited[0] = true;
+
+    while let Some(directory) = pending.pop() {
+        let directory = &directories[directory];
+        let start = T::read_u32(bytemuck::bytes_of(&directory.file_offset)) as usize;
+        let count = T::read_u16(bytemuck::bytes_of(&directory.file_count)) as usize;
+
+        for index in start..(start + count) {
+            let file = match files.get(index) {
+                Some(x) => x,
+                None => {
+                    errors.push(RarcError::MissingFile { index });
+                    break;
+                }
+            };
+
+            if file.index != u16::MAX || matches!(file.name::<T>(table), Some(".") | Some("..")) {
+                continue;
+            }
+
+            let target = T::read_u32(bytemuck::bytes_of(&file.offset_or_index)) as usize;
+            match visited.get_mut(target) {
+                None => errors.push(RarcError::MissingDirectory {
+                    index,
+                    directory: target,
+                }),
+                Some(true) => errors.push(RarcError::SharedDirectory { index: target }),
+                Some(seen) => {
+                    *seen = true;
+                    pending.push(target);
+                }
+            }
+        }
+    }
+}
+
+fn check_data<T: ByteOrder>(
+    header: &RarcHeader,
+    files: &[FileNode],
+    table: &StringTable,
+    errors: &mut Vec<RarcError>,
+) {
+    let name = |file: &FileNode| file.name::<T>(table).unwrap_or("<invaild>").to_string();
+
+    let mut ranges = files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| file.index != u16::MAX)
+        .map(|(index, file)| {
+            let start = T::read_u32(bytemuck::bytes_of(&file.offset_or_index)) as u64;
+            let size = T::read_u32(bytemuck::bytes_of(&file.size)) as u64;
+            (start, start + size, index, file)
+        })
+        .collect::<Vec<_>>();
+    ranges.sort_by_key(|(start, end, index, _)| (*start, *end, *index));
+
+    for (start, end, index, file) in &ranges {
+        if *end > header.data_length as u64 {
+            errors.push(RarcError::FileOutOfBounds {
+                name: name(file),
+                index: *index,
+                start: *start,
+                end: *end,
+                length: header.data_length,
+            });
+        }
+    }
+
+    let mut previous: Option<&(u64, u64, usize, &FileNode)> = None;
+    for range in ranges.iter().filter(|(start, end, _, _)| start != end) {
+        if let Some(last) = previous {
+            if range.0 < last.1 {
+                errors.push(RarcError::OverlappingFiles {
+                    first: name(last.3),
+                    first_index: last.2,
+                    second: name(range.3),
+                    second_index: range.2,
+                });
+            }
+
+            if range.1 <= last.1 {
+                continue;
+            }
+        }
+        previous = Some(range);
+    }
+}
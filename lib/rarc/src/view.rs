@@ -0,0 +1,209 @@
+use crate::archive::is_safe_name;
+use crate::header::RarcHeader;
+use crate::node::{DirectoryNode, FileNode};
+use crate::string_table;
+use crate::{RarcError, Result};
+use bytemuck::Pod;
+use byteorder::ByteOrder;
+use std::borrow::Cow;
+use std::io::{self, Cursor};
+use std::marker::PhantomData;
+use std::ops::Range;
+
+/// A read-only view over an uncompressed archive that is already in memory, like a memory-mapped file.
+/// Unlike [`Archive`](crate::Archive), it doesn't build an index and never copies file contents,
+/// and the node tables are only copied if they aren't aligned in the buffer.
+pub struct ArchiveView<'a, T: ByteOrder> {
+    data: &'a [u8],
+    directories: Cow<'a, [DirectoryNode]>,
+    files: Cow<'a, [FileNode]>,
+    strings: &'a [u8],
+    _order: PhantomData<T>,
+}
+
+impl<'a, T: ByteOrder> ArchiveView<'a, T> {
+    pub fn new(buffer: &'a [u8]) -> Result<Self> {
+        let header = RarcHeader::read::<T>(Cursor::new(buffer))?;
+
+        let directories: Cow<[DirectoryNode]> = cast_nodes(
+            buffer,
+            header.directory_offset as usize + 0x20,
+            header.directory_nodes as usize,
+        )?;
+        let files = cast_nodes(
+            buffer,
+            header.file_offset as usize + 0x20,
+            header.file_nodes as usize,
+        )?;
+        let strings = slice(
+            buffer,
+            header.string_offset as usize + 0x20,
+            header.string_size as usize,
+        )?;
+        let data = slice(
+            buffer,
+            header.data_offset as usize + 0x20,
+            header.data_length as usize,
+        )?;
+
+        if directories.first().is_some_and(|x| &x.tag != b"ROOT") {
+            return Err(RarcError::FirstDirectoryNotRoot);
+        }
+
+        Ok(ArchiveView {
+            data,
+            directories,
+            files,
+            strings,
+            _order: PhantomData,
+        })
+    }
+
+    pub fn directory_nodes(&self) -> &[DirectoryNode] {
+        &self.directories
+    }
+
+    pub fn file_nodes(&self) -> &[FileNode] {
+        &self.files
+    }
+
+    pub fn root_name(&self) -> Option<&'a str> {
+        let root = self.directories.first()?;
+        let offset = T::read_u32(bytemuck::bytes_of(&root.name_offset));
+        string_table::string_at(self.strings, offset as usize)
+    }
+
+    pub fn name(&self, node: &FileNode) -> Option<&'a str> {
+        let offset = T::read_u16(bytemuck::bytes_of(&node.name_offset));
+        string_table::string_at(self.strings, offset as usize)
+    }
+
+    /// Gets the contents of a file node as they are stored in the archive, without copying them.
+    /// Returns `None` for directories and files that lie outside of the data section.
+    pub fn file_data(&self, node: &FileNode) -> Option<&'a [u8]> {
+        self.data.get(node.data_range::<T>()?)
+    }
+
+    /// Looks up the file node at the given path by walking the directory nodes.
+    pub fn find(&self, path: &str) -> Option<&FileNode> {
+        let mut directory = 0;
+        let mut components = path.split('/').filter(|x| !x.is_empty()).peekable();
+
+        while let Some(component) = components.next() {
+            let range = self.directories.get(directory)?.file_range::<T>();
+            let node = self
+                .files
+                .get(range)?
+                .iter()
+                .find(|node| self.name(node) == Some(component))?;
+
+            if components.peek().is_none() {
+                return Some(node);
+            }
+            directory = node.directory_index::<T>()?;
+        }
+
+        None
+    }
+
+    /// Gets the contents of the file at the given path, without copying them.
+    pub fn get(&self, path: &str) -> Option<&'a [u8]> {
+        self.file_data(self.find(path)?)
+    }
+
+    /// Iterates over every file in the archive, together with its path.
+    /// Broken directory references are returned as errors, after which iteration stops.
+    pub fn files(&self) -> Files<'_, 'a, T> {
+        let mut visited = vec![false; self.directories.len()];
+        let pending = match visited.first_mut() {
+            Some(root) => {
+                *root = true;
+                vec![(String::new(), self.directories[0].file_range::<T>())]
+            }
+            None => Vec::new(),
+        };
+
+        Files {
+            view: self,
+            visited,
+            pending,
+        }
+    }
+}
+
+pub struct Files<'v, 'a, T: ByteOrder> {
+    view: &'v ArchiveView<'a, T>,
+    visited: Vec<bool>,
+    pending: Vec<(String, Range<usize>)>,
+}
+
+impl<'v, 'a, T: ByteOrder> Iterator for Files<'v, 'a, T> {
+    type Item = Result<(String, &'v FileNode)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (directory, range) = self.pending.last_mut()?;
+            let index = match range.next() {
+                Some(x) => x,
+                None => {
+                    self.pending.pop();
+                    continue;
+                }
+            };
+
+            let node = match self.view.files.get(index) {
+                Some(x) => x,
+                None => {
+                    self.pending.clear();
+                    return Some(Err(RarcError::MissingFile { index }));
+                }
+            };
+
+            let name = match self.view.name(node) {
+                Some(x) if x != "." && x != ".." => x,
+                _ => continue,
+            };
+            if !is_safe_name(name) {
+                self.pending.clear();
+                return Some(Err(RarcError::InvalidName { name: name.into() }));
+            }
+            let path = format!("{directory}/{name}");
+
+            let target = match node.directory_index::<T>() {
+                Some(x) => x,
+                None => return Some(Ok((path, node))),
+            };
+
+            let error = match self.visited.get_mut(target) {
+                None => RarcError::MissingDirectory {
+                    index,
+                    directory: target,
+                },
+                Some(true) => RarcError::SharedDirectory { index: target },
+                Some(visited) => {
+                    *visited = true;
+                    let range = self.view.directories[target].file_range::<T>();
+                    self.pending.push((path, range));
+                    continue;
+                }
+            };
+
+            self.pending.clear();
+            return Some(Err(error));
+        }
+    }
+}
+
+fn slice(buffer: &[u8], start: usize, length: usize) -> Result<&[u8]> {
+    buffer
+        .get(start..(start + length))
+        .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof).into())
+}
+
+fn cast_nodes<N: Pod>(buffer: &[u8], start: usize, count: usize) -> Result<Cow<'_, [N]>> {
+    let bytes = slice(buffer, start, count * std::mem::size_of::<N>())?;
+    Ok(match bytemuck::try_cast_slice(bytes) {
+        Ok(nodes) => Cow::Borrowed(nodes),
+        Err(_) => Cow::Owned(bytemuck::pod_collect_to_vec(bytes)),
+    })
+}
@@ -0,0 +1,130 @@
+use rarc::byteorder::BigEndian;
+use rarc::compression::Format;
+use rarc::{Archive, FileAttributes};
+use std::io::{Cursor, Read, Seek};
+
+fn contents() -> Vec<u8> {
+    b"Compressed members are decompressed transparently. ".repeat(20)
+}
+
+fn write<F: Read + Seek>(archive: &mut Archive<F>) -> Vec<u8> {
+    let mut result = Vec::new();
+    archive.write::<BigEndian>(&mut result).unwrap();
+    result
+}
+
+fn set_compression<F: Read + Seek>(archive: &mut Archive<F>, path: &str, format: Option<Format>) {
+    let mut file = archive.open_file(path).unwrap();
+    let mut attributes = file.attributes();
+    attributes.set_compression(format);
+    file.set_attributes(attributes);
+}
+
+/// An archive with a plain file and a file that gets stored with the given format.
+fn sample(format: Option<Format>) -> Archive<Cursor<Vec<u8>>> {
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_file("/plain", b"plain".to_vec()).unwrap();
+    archive.create_file("/packed", contents()).unwrap();
+    set_compression(&mut archive, "/packed", format);
+    Archive::from_vec::<BigEndian>(write(&mut archive)).unwrap()
+}
+
+#[test]
+fn yaz0_flag_needs_the_compressed_flag() {
+    assert_eq!(FileAttributes::YAZ0_COMPRESSED.compression(), None);
+    assert_eq!(
+        (FileAttributes::FILE | FileAttributes::YAZ0_COMPRESSED).compression(),
+        None
+    );
+    assert_eq!(FileAttributes::COMPRESSED.compression(), Some(Format::Yay0));
+    assert_eq!(
+        (FileAttributes::COMPRESSED | FileAttributes::YAZ0_COMPRESSED).compression(),
+        Some(Format::Yaz0)
+    );
+}
+
+#[test]
+fn yaz0_member_round_trip() {
+    let mut archive = sample(Some(Format::Yaz0));
+    let mut file = archive.open_file("/packed").unwrap();
+    assert!(file
+        .attributes()
+        .contains(FileAttributes::COMPRESSED | FileAttributes::YAZ0_COMPRESSED));
+    assert_eq!(file.stored_compression(), Some(Format::Yaz0));
+    assert_eq!(file.read_to_vec().unwrap(), contents());
+
+    let data = write(&mut archive);
+    let mut archive = Archive::from_vec::<BigEndian>(data.clone()).unwrap();
+    assert_eq!(
+        archive.open_file("/packed").unwrap().read_to_vec().unwrap(),
+        contents()
+    );
+    assert_eq!(write(&mut archive), data);
+}
+
+#[test]
+fn yaz0_flag_alone_is_stored_uncompressed() {
+    let mut archive = sample(None);
+    let mut file = archive.open_file("/packed").unwrap();
+    file.set_attributes(FileAttributes::FILE | FileAttributes::YAZ0_COMPRESSED);
+
+    let mut archive = Archive::from_vec::<BigEndian>(write(&mut archive)).unwrap();
+    let mut file = archive.open_file("/packed").unwrap();
+    assert_eq!(file.stored_compression(), None);
+    assert_eq!(file.read_raw_to_vec().unwrap(), contents());
+}
+
+#[test]
+fn compression_changes_are_encoded_on_write() {
+    let mut archive = sample(Some(Format::Yaz0));
+    set_compression(&mut archive, "/packed", Some(Format::Yay0));
+    set_compression(&mut archive, "/plain", Some(Format::Yaz0));
+
+    // The stored contents only change once the archive is written.
+    let file = archive.open_file("/packed").unwrap();
+    assert_eq!(file.stored_compression(), Some(Format::Yaz0));
+
+    let mut archive = Archive::from_vec::<BigEndian>(write(&mut archive)).unwrap();
+    let mut file = archive.open_file("/packed").unwrap();
+    assert_eq!(file.stored_compression(), Some(Format::Yay0));
+    assert_eq!(
+        Format::detect(&file.read_raw_to_vec().unwrap()),
+        Some(Format::Yay0)
+    );
+    assert_eq!(file.read_to_vec().unwrap(), contents());
+
+    let mut file = archive.open_file("/plain").unwrap();
+    assert_eq!(
+        Format::detect(&file.read_raw_to_vec().unwrap()),
+        Some(Format::Yaz0)
+    );
+    assert_eq!(file.read_to_vec().unwrap(), b"plain");
+
+    set_compression(&mut archive, "/packed", None);
+    let mut archive = Archive::from_vec::<BigEndian>(write(&mut archive)).unwrap();
+    let mut file = archive.open_file("/packed").unwrap();
+    assert_eq!(file.stored_compression(), None);
+    assert_eq!(file.read_raw_to_vec().unwrap(), contents());
+}
+
+#[test]
+fn raw_reads() {
+    let mut archive = sample(Some(Format::Yay0));
+    let mut file = archive.open_file("/packed").unwrap();
+
+    let raw = file.read_raw_to_vec().unwrap();
+    assert_eq!(raw.len(), file.size() as usize);
+    assert_eq!(Format::detect(&raw), Some(Format::Yay0));
+    assert!(raw.len() < contents().len());
+    assert_eq!(yaz0::decompress(&raw).unwrap(), contents());
+
+    let mut buffer = Vec::new();
+    file.raw_reader().unwrap().read_to_end(&mut buffer).unwrap();
+    assert_eq!(buffer, raw);
+
+    let mut buffer = Vec::new();
+    let mut reader = file.reader().unwrap();
+    assert_eq!(reader.size(), contents().len() as u64);
+    reader.read_to_end(&mut buffer).unwrap();
+    assert_eq!(buffer, contents());
+}
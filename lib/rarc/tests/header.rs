@@ -0,0 +1,277 @@
+use rarc::byteorder::{BigEndian, ByteOrder, LittleEndian};
+use rarc::compression::Format;
+use rarc::{validate, Archive, Endian, RarcError};
+use std::io::{Cursor, ErrorKind};
+
+fn sample<T: ByteOrder>() -> Vec<u8> {
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_file("/a/first", b"first".to_vec()).unwrap();
+    archive
+        .create_file("/b/second", b"second".to_vec())
+        .unwrap();
+    archive.create_file("/third", b"third".to_vec()).unwrap();
+
+    let mut data = Vec::new();
+    archive.write::<T>(&mut data).unwrap();
+    data
+}
+
+fn read(data: &[u8]) -> Result<Archive<Cursor<Vec<u8>>>, RarcError> {
+    Archive::from_vec::<BigEndian>(data.to_vec())
+}
+
+/// Gets the position of the first file node with the given name.
+fn file_node(data: &[u8], name: &str) -> usize {
+    let file_offset = BigEndian::read_u32(&data[0x2C..]) as usize + 0x20;
+    let file_nodes = BigEndian::read_u32(&data[0x28..]) as usize;
+    let string_offset = BigEndian::read_u32(&data[0x34..]) as usize + 0x20;
+
+    (0..file_nodes)
+        .map(|i| file_offset + i * 0x14)
+        .find(|node| {
+            let start = string_offset + BigEndian::read_u16(&data[(node + 6)..]) as usize;
+            data[start..].starts_with(name.as_bytes()) && data[start + name.len()] == 0
+        })
+        .unwrap()
+}
+
+#[test]
+fn detect_byte_order() {
+    let (_, endian) = Archive::from_vec_auto(sample::<BigEndian>()).unwrap();
+    assert_eq!(endian, Endian::Big);
+    let (_, endian) = Archive::from_vec_auto(sample::<LittleEndian>()).unwrap();
+    assert_eq!(endian, Endian::Little);
+
+    let mut data = sample::<BigEndian>();
+    BigEndian::write_u32(&mut data[0x08..], 0x40);
+    assert!(matches!(
+        Archive::from_vec_auto(data),
+        Err(RarcError::UnknownByteOrder)
+    ));
+}
+
+#[test]
+fn detect_byte_order_of_files() {
+    for (name, endian, data) in [
+        ("big", Endian::Big, sample::<BigEndian>()),
+        ("little", Endian::Little, sample::<LittleEndian>()),
+        (
+            "big-yaz0",
+            Endian::Big,
+            yaz0::compress(&sample::<BigEndian>(), Format::Yaz0, Default::default()),
+        ),
+        (
+            "little-yay0",
+            Endian::Little,
+            yaz0::compress(&sample::<LittleEndian>(), Format::Yay0, Default::default()),
+        ),
+    ] {
+        let path =
+            std::env::temp_dir().join(format!("rarc-detect-{name}-{}.arc", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+        let result = Archive::open_auto(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let (mut archive, detected) = result.unwrap();
+        assert_eq!(detected, endian, "{name}");
+        assert_eq!(
+            archive.open_file("/third").unwrap().read_to_vec().unwrap(),
+            b"third"
+        );
+    }
+
+    let (_, endian) = Archive::read_auto(Cursor::new(sample::<LittleEndian>())).unwrap();
+    assert_eq!(endian, Endian::Little);
+}
+
+#[test]
+fn bad_magic() {
+    let mut data = sample::<BigEndian>();
+    data[..4].copy_from_slice(b"CRAR");
+    assert!(matches!(read(&data), Err(RarcError::InvaildMagic)));
+    assert!(matches!(
+        Archive::from_vec_auto(data.clone()),
+        Err(RarcError::InvaildMagic)
+    ));
+    assert!(matches!(
+        validate::<BigEndian>(Cursor::new(data)).as_slice(),
+        [RarcError::InvaildMagic]
+    ));
+}
+
+#[test]
+fn truncated_header() {
+    let data = sample::<BigEndian>();
+    for length in [0, 3, 0x0B, 0x1F] {
+        let error = read(&data[..length]).err().unwrap();
+        assert!(
+            matches!(&error, RarcError::IoError(e) if e.kind() == ErrorKind::UnexpectedEof),
+            "{length:#x}: {error}"
+        );
+    }
+    assert!(Archive::from_vec_auto(data[..0x0B].to_vec()).is_err());
+
+    // Once the file header is complete, the sections are checked against the length.
+    for length in [0x20, 0x3F, 0x40] {
+        assert!(matches!(
+            read(&data[..length]),
+            Err(RarcError::HeaderOutOfBounds { field: "size", .. })
+        ));
+    }
+    assert!(matches!(
+        read(&data[..(data.len() - 1)]),
+        Err(RarcError::HeaderOutOfBounds { field: "size", .. })
+    ));
+}
+
+#[test]
+fn sections_out_of_range() {
+    let data = sample::<BigEndian>();
+    for (field, offset) in [
+        ("data_length", 0x10),
+        ("directory_nodes", 0x20),
+        ("file_nodes", 0x28),
+        ("string_size", 0x30),
+    ] {
+        let mut data = data.clone();
+        BigEndian::write_u32(&mut data[offset..], 0x10000);
+        assert!(
+            matches!(
+                read(&data),
+                Err(RarcError::HeaderOutOfBounds { field: x, offset: y, value: 0x10000, .. })
+                    if x == field && y == offset as u64
+            ),
+            "{field}"
+        );
+    }
+
+    // The offset of a section counts as well.
+    let mut data = data;
+    BigEndian::write_u32(&mut data[0x34..], 0x10000);
+    assert!(matches!(
+        read(&data),
+        Err(RarcError::HeaderOutOfBounds {
+            field: "string_size",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn load_class_sizes_out_of_range() {
+    let data = sample::<BigEndian>();
+    let data_length = BigEndian::read_u32(&data[0x10..]);
+    assert_eq!(BigEndian::read_u32(&data[0x14..]), data_length);
+
+    let mut data = data.clone();
+    BigEndian::write_u32(&mut data[0x14..], data_length - 0x20);
+    BigEndian::write_u32(&mut data[0x1C..], 0x20);
+    assert!(read(&data).is_ok());
+
+    BigEndian::write_u32(&mut data[0x18..], 0x20);
+    assert!(matches!(
+        read(&data),
+        Err(RarcError::LoadSizeOutOfBounds { field: "dvd", offset: 0x1C, end, length, .. })
+            if end == data_length as u64 + 0x20 && length == data_length
+    ));
+
+    BigEndian::write_u32(&mut data[0x14..], data_length + 1);
+    assert!(matches!(
+        read(&data),
+        Err(RarcError::LoadSizeOutOfBounds { field: "mram", .. })
+    ));
+    assert!(matches!(
+        validate::<BigEndian>(Cursor::new(data)).as_slice(),
+        [RarcError::LoadSizeOutOfBounds { .. }]
+    ));
+}
+
+#[test]
+fn valid_archive() {
+    assert!(validate::<BigEndian>(Cursor::new(sample::<BigEndian>())).is_empty());
+    assert!(validate::<LittleEndian>(Cursor::new(sample::<LittleEndian>())).is_empty());
+}
+
+#[test]
+fn shared_directory() {
+    let mut data = sample::<BigEndian>();
+    let a = file_node(&data, "a");
+    let b = file_node(&data, "b");
+    let target = data[(a + 8)..(a + 12)].to_vec();
+    data[(b + 8)..(b + 12)].copy_from_slice(&target);
+
+    let index = BigEndian::read_u32(&target) as usize;
+    assert!(matches!(
+        read(&data),
+        Err(RarcError::SharedDirectory { index: x }) if x == index
+    ));
+    assert!(matches!(
+        validate::<BigEndian>(Cursor::new(data)).as_slice(),
+        [RarcError::SharedDirectory { index: x }] if *x == index
+    ));
+}
+
+#[test]
+fn missing_directory() {
+    let mut data = sample::<BigEndian>();
+    let a = file_node(&data, "a");
+    BigEndian::write_u32(&mut data[(a + 8)..], 100);
+    assert!(matches!(
+        validate::<BigEndian>(Cursor::new(data)).as_slice(),
+        [RarcError::MissingDirectory { directory: 100, .. }]
+    ));
+}
+
+#[test]
+fn overlapping_files() {
+    let mut data = sample::<BigEndian>();
+    let first = file_node(&data, "first");
+    let second = file_node(&data, "second");
+    let offset = BigEndian::read_u32(&data[(first + 8)..]);
+    BigEndian::write_u32(&mut data[(second + 8)..], offset + 2);
+
+    let errors = validate::<BigEndian>(Cursor::new(data.clone()));
+    assert!(matches!(
+        errors.as_slice(),
+        [RarcError::OverlappingFiles { first, second, .. }] if first == "first" && second == "second"
+    ));
+
+    // Overlapping files can still be read.
+    let mut archive = read(&data).unwrap();
+    assert_eq!(
+        archive
+            .open_file("/b/second")
+            .unwrap()
+            .read_to_vec()
+            .unwrap(),
+        b"rst\0\0\0"
+    );
+}
+
+#[test]
+fn file_out_of_bounds() {
+    let mut data = sample::<BigEndian>();
+    let third = file_node(&data, "third");
+    BigEndian::write_u32(&mut data[(third + 12)..], 0x1000);
+    assert!(matches!(
+        validate::<BigEndian>(Cursor::new(data)).as_slice(),
+        [RarcError::FileOutOfBounds { name, end, .. }] if name == "third" && *end > 0x1000
+    ));
+}
+
+#[test]
+fn name_out_of_range() {
+    let mut data = sample::<BigEndian>();
+    let third = file_node(&data, "third");
+    BigEndian::write_u16(&mut data[(third + 6)..], 0x1000);
+    assert!(validate::<BigEndian>(Cursor::new(data))
+        .iter()
+        .any(|x| matches!(
+            x,
+            RarcError::NameOutOfRange {
+                node: "file",
+                name_offset: 0x1000,
+                ..
+            }
+        )));
+}
@@ -0,0 +1,79 @@
+use rarc::byteorder::{BigEndian, ByteOrder, LittleEndian};
+use rarc::{Archive, ArchiveView, LoadClass};
+
+/// Writes one file per load class, which are created in a different order than their data is grouped by.
+fn sample<T: ByteOrder>() -> Vec<u8> {
+    let mut archive = Archive::new("stage").unwrap();
+    let files: [(&str, usize, Option<LoadClass>); 4] = [
+        ("/a_dvd", 70, Some(LoadClass::Dvd)),
+        ("/b_none", 5, None),
+        ("/c_aram", 40, Some(LoadClass::Aram)),
+        ("/d_mram", 10, Some(LoadClass::Mram)),
+    ];
+    for (path, size, class) in files {
+        archive.create_file(path, vec![size as u8; size]).unwrap();
+        archive.open_file(path).unwrap().set_load_class(class);
+    }
+
+    let mut data = Vec::new();
+    archive.write::<T>(&mut data).unwrap();
+    data
+}
+
+fn grouped_by_load_class<T: ByteOrder>() {
+    let data = sample::<T>();
+    assert_eq!(T::read_u32(&data[0x10..]), 224);
+    assert_eq!(T::read_u32(&data[0x14..]), 32);
+    assert_eq!(T::read_u32(&data[0x18..]), 64);
+    assert_eq!(T::read_u32(&data[0x1C..]), 96);
+
+    let view = ArchiveView::<T>::new(&data).unwrap();
+    let range = |path| view.find(path).unwrap().data_range::<T>().unwrap();
+    assert_eq!(range("/d_mram"), 0..10);
+    assert_eq!(range("/c_aram"), 32..72);
+    assert_eq!(range("/a_dvd"), 96..166);
+    assert_eq!(range("/b_none"), 192..197);
+
+    // Reading and writing the archive again keeps the layout.
+    let mut archive = Archive::from_vec::<T>(data.clone()).unwrap();
+    assert_eq!(
+        archive.open_file("/c_aram").unwrap().load_class(),
+        Some(LoadClass::Aram)
+    );
+    let mut written = Vec::new();
+    archive.write::<T>(&mut written).unwrap();
+    assert_eq!(written, data);
+}
+
+#[test]
+fn grouped_by_load_class_big_endian() {
+    grouped_by_load_class::<BigEndian>();
+}
+
+#[test]
+fn grouped_by_load_class_little_endian() {
+    grouped_by_load_class::<LittleEndian>();
+}
+
+#[test]
+fn changed_load_class_moves_the_data() {
+    let mut archive = Archive::from_vec::<BigEndian>(sample::<BigEndian>()).unwrap();
+    archive
+        .open_file("/a_dvd")
+        .unwrap()
+        .set_load_class(Some(LoadClass::Mram));
+
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+    assert_eq!(BigEndian::read_u32(&data[0x14..]), 128);
+    assert_eq!(BigEndian::read_u32(&data[0x18..]), 64);
+    assert_eq!(BigEndian::read_u32(&data[0x1C..]), 0);
+
+    let view = ArchiveView::<BigEndian>::new(&data).unwrap();
+    let range = |path| view.find(path).unwrap().data_range::<BigEndian>().unwrap();
+    // Within a group, the data keeps its original order.
+    assert_eq!(range("/d_mram"), 0..10);
+    assert_eq!(range("/a_dvd"), 32..102);
+    assert_eq!(range("/c_aram"), 128..168);
+    assert_eq!(view.get("/a_dvd").unwrap(), &[70; 70]);
+}
@@ -0,0 +1,147 @@
+use rarc::byteorder::{BigEndian, ByteOrder};
+use rarc::{name_hash, Archive, ArchiveView, HashCheck, RarcError, ReadOptions};
+
+/// Writes an archive with a file, whose name gets replaced by one that escapes the extraction directory.
+fn crafted(name: &str) -> Vec<u8> {
+    let placeholder = "x".repeat(name.len());
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_directory("/jmp").unwrap();
+    archive
+        .create_file(&format!("/jmp/{placeholder}"), b"payload".to_vec())
+        .unwrap();
+
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+
+    let string_offset = BigEndian::read_u32(&data[0x34..]) as usize + 0x20;
+    let string_size = BigEndian::read_u32(&data[0x30..]) as usize;
+    let strings = &mut data[string_offset..(string_offset + string_size)];
+    let start = strings
+        .windows(placeholder.len())
+        .position(|x| x == placeholder.as_bytes())
+        .unwrap();
+    strings[start..(start + name.len())].copy_from_slice(name.as_bytes());
+
+    let file_offset = BigEndian::read_u32(&data[0x2C..]) as usize + 0x20;
+    let file_nodes = BigEndian::read_u32(&data[0x28..]) as usize;
+    let stale = name_hash(&placeholder);
+    for node in data[file_offset..(file_offset + file_nodes * 0x14)].chunks_mut(0x14) {
+        if BigEndian::read_u16(&node[2..]) == stale {
+            BigEndian::write_u16(&mut node[2..], name_hash(name));
+        }
+    }
+    data
+}
+
+#[test]
+fn unsafe_names_are_rejected_on_read() {
+    for name in ["../../pwned_", "..\\..\\pwned", "/abs"] {
+        let data = crafted(name);
+        assert!(matches!(
+            Archive::from_vec::<BigEndian>(data.clone()),
+            Err(RarcError::InvalidName { name: x }) if x == name
+        ));
+
+        // The files iterator returns the error once and stops afterwards.
+        let view = ArchiveView::<BigEndian>::new(&data).unwrap();
+        let mut files = view.files();
+        assert!(matches!(
+            files.next(),
+            Some(Err(RarcError::InvalidName { name: x })) if x == name
+        ));
+        assert!(files.next().is_none());
+    }
+}
+
+#[test]
+fn crafted_archive_reads_with_a_safe_name() {
+    let mut archive = Archive::from_vec::<BigEndian>(crafted("pwned_")).unwrap();
+    assert_eq!(
+        archive
+            .open_file("/jmp/pwned_")
+            .unwrap()
+            .read_to_vec()
+            .unwrap(),
+        b"payload"
+    );
+}
+
+#[test]
+fn unsafe_names_are_rejected_on_create() {
+    let mut archive = Archive::new("stage").unwrap();
+    assert!(archive.create_file("/a\\b", Vec::new()).is_err());
+    assert!(archive.create_directory("/jmp/..").is_err());
+    assert!(Archive::new("a/b").is_err());
+}
+
+/// Writes an archive and replaces the name hash of the `ObjInfo` file node with a stale one.
+fn stale_hash() -> Vec<u8> {
+    let mut archive = Archive::new("stage").unwrap();
+    archive
+        .create_file("/jmp/ObjInfo", b"objects".to_vec())
+        .unwrap();
+
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+
+    let file_offset = BigEndian::read_u32(&data[0x2C..]) as usize + 0x20;
+    let file_nodes = BigEndian::read_u32(&data[0x28..]) as usize;
+    let hash = name_hash("ObjInfo");
+    let node = data[file_offset..(file_offset + file_nodes * 0x14)]
+        .chunks_mut(0x14)
+        .find(|node| BigEndian::read_u16(&node[2..]) == hash)
+        .unwrap();
+    BigEndian::write_u16(&mut node[2..], 0x1234);
+    data
+}
+
+#[test]
+fn lenient_hash_check_is_the_default() {
+    assert_eq!(ReadOptions::default().hash_check, HashCheck::Lenient);
+    assert_eq!(HashCheck::default(), HashCheck::Lenient);
+}
+
+#[test]
+fn strict_hash_check() {
+    let options = ReadOptions {
+        hash_check: HashCheck::Strict,
+    };
+    assert!(matches!(
+        Archive::from_vec_with::<BigEndian>(stale_hash(), options),
+        Err(RarcError::HashMismatch { path, stored: 0x1234, calculated })
+            if path == "/jmp/ObjInfo" && calculated == name_hash("ObjInfo")
+    ));
+}
+
+#[test]
+fn lenient_hash_check() {
+    let options = ReadOptions {
+        hash_check: HashCheck::Lenient,
+    };
+    let lenient = Archive::from_vec_with::<BigEndian>(stale_hash(), options).unwrap();
+    let mut default = Archive::from_vec::<BigEndian>(stale_hash()).unwrap();
+
+    for archive in [&lenient, &default] {
+        assert!(matches!(
+            archive.warnings(),
+            [RarcError::HashMismatch { path, stored: 0x1234, .. }] if path == "/jmp/ObjInfo"
+        ));
+    }
+    assert_eq!(
+        default
+            .open_file("/jmp/ObjInfo")
+            .unwrap()
+            .read_to_vec()
+            .unwrap(),
+        b"objects"
+    );
+
+    // Writing the archive regenerates the hash.
+    let mut data = Vec::new();
+    default.write::<BigEndian>(&mut data).unwrap();
+    let options = ReadOptions {
+        hash_check: HashCheck::Strict,
+    };
+    let archive = Archive::from_vec_with::<BigEndian>(data, options).unwrap();
+    assert!(archive.warnings().is_empty());
+}
@@ -0,0 +1,91 @@
+use rarc::byteorder::BigEndian;
+use rarc::Archive;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+
+/// An archive with two files, whose contents lie right next to each other in the data section.
+fn sample() -> Archive<std::io::Cursor<Vec<u8>>> {
+    let mut archive = Archive::new("stage").unwrap();
+    archive
+        .create_file("/first", b"0123456789".to_vec())
+        .unwrap();
+    archive.create_file("/second", vec![0xAB; 64]).unwrap();
+
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+    Archive::from_vec::<BigEndian>(data).unwrap()
+}
+
+#[test]
+fn seek_past_the_end() {
+    let mut archive = sample();
+    let mut file = archive.open_file("/first").unwrap();
+    let mut reader = file.reader().unwrap();
+
+    assert_eq!(reader.seek(SeekFrom::Start(100)).unwrap(), 100);
+    let mut buffer = [0; 4];
+    assert_eq!(reader.read(&mut buffer).unwrap(), 0);
+
+    assert_eq!(reader.seek(SeekFrom::End(5)).unwrap(), 15);
+    assert_eq!(reader.read(&mut buffer).unwrap(), 0);
+
+    // Seeking back makes the contents readable again.
+    assert_eq!(reader.seek(SeekFrom::Current(-13)).unwrap(), 2);
+    reader.read_exact(&mut buffer).unwrap();
+    assert_eq!(&buffer, b"2345");
+}
+
+#[test]
+fn seek_from_the_end() {
+    let mut archive = sample();
+    let mut file = archive.open_file("/first").unwrap();
+    let mut reader = file.reader().unwrap();
+
+    assert_eq!(reader.seek(SeekFrom::End(-3)).unwrap(), 7);
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer).unwrap();
+    assert_eq!(buffer, b"789");
+
+    assert_eq!(reader.seek(SeekFrom::End(-10)).unwrap(), 0);
+    assert_eq!(
+        reader.seek(SeekFrom::End(-11)).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        reader.seek(SeekFrom::Current(-1)).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn read_across_the_end() {
+    let mut archive = sample();
+    let mut file = archive.open_file("/first").unwrap();
+    let mut reader = file.reader().unwrap();
+    assert_eq!(reader.size(), 10);
+
+    // The data of the next file starts after the padding and is never read.
+    reader.seek(SeekFrom::Start(8)).unwrap();
+    let mut buffer = [0xFF; 64];
+    assert_eq!(reader.read(&mut buffer).unwrap(), 2);
+    assert_eq!(&buffer[..3], b"89\xFF");
+    assert_eq!(reader.read(&mut buffer).unwrap(), 0);
+
+    reader.seek(SeekFrom::Start(8)).unwrap();
+    assert_eq!(
+        reader.read_exact(&mut [0; 4]).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn replaced_contents_are_bounded() {
+    let mut archive = sample();
+    let mut file = archive.open_file("/second").unwrap();
+    file.replace(b"replaced".to_vec()).unwrap();
+
+    let mut reader = file.reader().unwrap();
+    assert_eq!(reader.seek(SeekFrom::End(-2)).unwrap(), 6);
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer).unwrap();
+    assert_eq!(buffer, b"ed");
+}
@@ -0,0 +1,284 @@
+use rarc::byteorder::{BigEndian, ByteOrder, LittleEndian};
+use rarc::{create_tag, name_hash, Archive};
+use std::io::Cursor;
+
+fn sample() -> Archive<std::io::Empty> {
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_directory("/jmp/Placement").unwrap();
+    archive.create_directory("/empty").unwrap();
+    archive
+        .create_file("/jmp/Placement/ObjInfo", b"objects".to_vec())
+        .unwrap();
+    archive
+        .create_file("/jmp/Placement/AreaObjInfo", vec![0xAB; 100])
+        .unwrap();
+    archive
+        .create_file("/readme.txt", b"hello".to_vec())
+        .unwrap();
+    archive.create_file("/zero", Vec::new()).unwrap();
+    archive
+}
+
+fn write<T: ByteOrder, F: std::io::Read + std::io::Seek>(archive: &mut Archive<F>) -> Vec<u8> {
+    let mut result = Vec::new();
+    archive.write::<T>(&mut result).unwrap();
+    result
+}
+
+fn round_trip<T: ByteOrder>() {
+    let first = write::<T, _>(&mut sample());
+
+    let mut archive = Archive::from_vec::<T>(first.clone()).unwrap();
+    assert_eq!(
+        archive
+            .open_file("/jmp/Placement/ObjInfo")
+            .unwrap()
+            .read_to_vec()
+            .unwrap(),
+        b"objects"
+    );
+    let second = write::<T, _>(&mut archive);
+    assert_eq!(second, first);
+
+    let mut archive = Archive::from_vec::<T>(second).unwrap();
+    assert_eq!(write::<T, _>(&mut archive), first);
+}
+
+#[test]
+fn big_endian() {
+    round_trip::<BigEndian>();
+}
+
+#[test]
+fn little_endian() {
+    round_trip::<LittleEndian>();
+}
+
+fn embedded<T: ByteOrder>() {
+    let archive = write::<T, _>(&mut sample());
+    let mut data = b"some data before the archive".to_vec();
+    let start = data.len() as u64;
+    data.extend(&archive);
+    data.extend(b"and some after it");
+
+    let mut reader = Cursor::new(data);
+    reader.set_position(start);
+    let mut archive = Archive::read::<T>(reader).unwrap();
+    assert_eq!(
+        archive
+            .open_file("/jmp/Placement/AreaObjInfo")
+            .unwrap()
+            .read_to_vec()
+            .unwrap(),
+        vec![0xAB; 100]
+    );
+    assert_eq!(write::<T, _>(&mut archive), write::<T, _>(&mut sample()));
+}
+
+#[test]
+fn embedded_big_endian() {
+    embedded::<BigEndian>();
+}
+
+#[test]
+fn embedded_little_endian() {
+    embedded::<LittleEndian>();
+}
+
+#[test]
+fn stale_next_file_id() {
+    let mut archive = sample();
+    archive.set_keep_synced(false);
+    let mut data = write::<BigEndian, _>(&mut archive);
+    assert_eq!(BigEndian::read_u16(&data[0x38..]), 4);
+
+    // Reading and writing keeps the stored value, even though it is already in use.
+    BigEndian::write_u16(&mut data[0x38..], 1);
+    let mut archive = Archive::from_vec::<BigEndian>(data.clone()).unwrap();
+    assert_eq!(archive.next_file_id(), 1);
+    assert_eq!(write::<BigEndian, _>(&mut archive), data);
+
+    archive.create_file("/new", b"new".to_vec()).unwrap();
+    assert_eq!(archive.open_file("/new").unwrap().id(), 4);
+    assert_eq!(archive.next_file_id(), 5);
+}
+
+/// A node of [`nintendo`], either a directory with the index of its node or a file with its contents.
+enum Node {
+    Directory(&'static str, u32),
+    File(&'static str, &'static [u8]),
+}
+
+/// Builds an archive laid out the way Nintendo's tools write them. Unlike a breadth-first layout,
+/// the directory nodes are stored depth-first, so `Placement` comes before `obj`.
+/// The data section isn't in node order either.
+fn nintendo<T: ByteOrder>() -> Vec<u8> {
+    const MODEL: &[u8] = &[0x11; 40];
+    let directories: [(&str, &[Node]); 4] = [
+        (
+            "scene",
+            &[
+                Node::Directory("jmp", 1),
+                Node::Directory("obj", 3),
+                Node::File("readme", b"hello"),
+            ],
+        ),
+        (
+            "jmp",
+            &[Node::Directory("Placement", 2), Node::File("List", b"list")],
+        ),
+        ("Placement", &[Node::File("ObjInfo", b"objects")]),
+        ("obj", &[Node::File("model", MODEL)]),
+    ];
+    let parents = [u32::MAX, 0, 1, 0];
+    let data: [&[u8]; 4] = [b"objects", MODEL, b"list", b"hello"];
+
+    let mut strings = b".\0..\0".to_vec();
+    let mut string = |name: &str| match strings
+        .windows(name.len() + 1)
+        .position(|x| &x[..name.len()] == name.as_bytes() && x[name.len()] == 0)
+    {
+        Some(offset) => offset as u32,
+        None => {
+            let offset = strings.len() as u32;
+            strings.extend(name.as_bytes());
+            strings.push(0);
+            offset
+        }
+    };
+
+    let mut directory_nodes = Vec::new();
+    let mut file_nodes = Vec::new();
+    let file_node = |index: u16, name: u32, hash: u16, attributes: u8, offset: u32, size: u32| {
+        let mut node = [0; 0x14];
+        T::write_u16(&mut node[0x00..], index);
+        T::write_u16(&mut node[0x02..], hash);
+        node[0x04] = attributes;
+        T::write_u16(&mut node[0x06..], name as u16);
+        T::write_u32(&mut node[0x08..], offset);
+        T::write_u32(&mut node[0x0C..], size);
+        node
+    };
+
+    let mut count = 0;
+    for (i, (name, entries)) in directories.iter().enumerate() {
+        let tag = if i == 0 { *b"ROOT" } else { create_tag(name) };
+        let mut node = [0; 0x10];
+        node[..4].copy_from_slice(&tag);
+        T::write_u32(&mut node[0x04..], string(name));
+        T::write_u16(&mut node[0x08..], name_hash(name));
+        T::write_u16(&mut node[0x0A..], entries.len() as u16 + 2);
+        T::write_u32(&mut node[0x0C..], count);
+        directory_nodes.extend(node);
+
+        for entry in entries.iter() {
+            let node = match entry {
+                Node::Directory(name, index) => {
+                    file_node(u16::MAX, string(name), name_hash(name), 0x02, *index, 0x10)
+                }
+                Node::File(name, contents) => {
+                    let position = data.iter().position(|x| x == contents).unwrap();
+                    let offset = data[..position]
+                        .iter()
+                        .map(|x| (x.len() as u32 + 31) & !31)
+                        .sum();
+                    let id = (file_nodes.len() / 0x14) as u16;
+                    let name_offset = string(name);
+                    file_node(
+                        id,
+                        name_offset,
+                        name_hash(name),
+                        0x11,
+                        offset,
+                        contents.len() as u32,
+                    )
+                }
+            };
+            file_nodes.extend(node);
+        }
+        file_nodes.extend(file_node(u16::MAX, 0, name_hash("."), 0x02, i as u32, 0x10));
+        file_nodes.extend(file_node(
+            u16::MAX,
+            2,
+            name_hash(".."),
+            0x02,
+            parents[i],
+            0x10,
+        ));
+        count += entries.len() as u32 + 2;
+    }
+
+    let mut contents = Vec::new();
+    for file in data {
+        contents.extend(file);
+        contents.resize((contents.len() + 31) & !31, 0);
+    }
+
+    directory_nodes.resize(align(directory_nodes.len()), 0);
+    file_nodes.resize(align(file_nodes.len()), 0);
+    strings.resize(align(strings.len()), 0);
+
+    let directory_offset = 0x20;
+    let file_offset = directory_offset + directory_nodes.len() as u32;
+    let string_offset = file_offset + file_nodes.len() as u32;
+    let data_offset = string_offset + strings.len() as u32;
+    let data_length = contents.len() as u32;
+
+    let mut result = vec![0; 0x40];
+    result[..4].copy_from_slice(b"RARC");
+    T::write_u32_into(
+        &[
+            0x20 + data_offset + data_length,
+            0x20,
+            data_offset,
+            data_length,
+            data_length,
+            0,
+            0,
+            4,
+            directory_offset,
+            count,
+            file_offset,
+            strings.len() as u32,
+            string_offset,
+        ],
+        &mut result[0x04..0x38],
+    );
+    T::write_u16(&mut result[0x38..], count as u16);
+    result[0x3A] = 1;
+
+    result.extend(directory_nodes);
+    result.extend(file_nodes);
+    result.extend(strings);
+    result.extend(contents);
+    result
+}
+
+fn align(value: usize) -> usize {
+    (value + 31) & !31
+}
+
+fn nintendo_layout<T: ByteOrder>() {
+    let original = nintendo::<T>();
+    let mut archive = Archive::from_vec::<T>(original.clone()).unwrap();
+    assert!(archive.warnings().is_empty());
+    assert_eq!(
+        archive
+            .open_file("/jmp/Placement/ObjInfo")
+            .unwrap()
+            .read_to_vec()
+            .unwrap(),
+        b"objects"
+    );
+    assert_eq!(write::<T, _>(&mut archive), original);
+}
+
+#[test]
+fn nintendo_layout_big_endian() {
+    nintendo_layout::<BigEndian>();
+}
+
+#[test]
+fn nintendo_layout_little_endian() {
+    nintendo_layout::<LittleEndian>();
+}
@@ -0,0 +1,127 @@
+use rarc::byteorder::{BigEndian, ByteOrder, LittleEndian};
+use rarc::{create_tag, Archive, RarcError};
+
+#[test]
+fn create_tag_pads_and_upper_cases() {
+    assert_eq!(&create_tag("jmp"), b"JMP ");
+    assert_eq!(&create_tag("a"), b"A   ");
+    assert_eq!(&create_tag(""), b"    ");
+    assert_eq!(&create_tag("Placement"), b"PLAC");
+    assert_eq!(&create_tag("mapparts"), b"MAPP");
+    assert_eq!(&create_tag("a_1"), b"A_1 ");
+}
+
+fn custom_tag<T: ByteOrder>() {
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_directory("/jmp/Placement").unwrap();
+    archive.create_directory("/camera").unwrap();
+    archive.set_directory_tag("/jmp", Some(*b"xYz!")).unwrap();
+
+    let mut data = Vec::new();
+    archive.write::<T>(&mut data).unwrap();
+    assert!(data.windows(4).any(|x| x == b"xYz!"));
+
+    let mut archive = Archive::from_vec::<T>(data).unwrap();
+    assert_eq!(archive.directory_tag("/"), Some(*b"ROOT"));
+    assert_eq!(archive.directory_tag("/jmp"), Some(*b"xYz!"));
+    assert_eq!(archive.directory_tag("/jmp/Placement"), Some(*b"PLAC"));
+    assert_eq!(archive.directory_tag("/camera"), Some(*b"CAME"));
+
+    // Moving a directory keeps its tag, even though its name changes.
+    archive.mv("/jmp", "/map").unwrap();
+    assert_eq!(archive.directory_tag("/map"), Some(*b"xYz!"));
+
+    // Without an explicit tag, it is generated from the name again.
+    archive.set_directory_tag("/map", None).unwrap();
+    assert_eq!(archive.directory_tag("/map"), Some(*b"MAP "));
+}
+
+#[test]
+fn custom_tag_big_endian() {
+    custom_tag::<BigEndian>();
+}
+
+#[test]
+fn custom_tag_little_endian() {
+    custom_tag::<LittleEndian>();
+}
+
+#[test]
+fn tags_of_other_entries() {
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_file("/file", Vec::new()).unwrap();
+
+    assert_eq!(archive.directory_tag("/file"), None);
+    assert_eq!(archive.directory_tag("/missing"), None);
+    assert!(matches!(
+        archive.set_directory_tag("/", Some(*b"TEST")),
+        Err(RarcError::RootDirectory)
+    ));
+    assert!(matches!(
+        archive.set_directory_tag("/file", Some(*b"TEST")),
+        Err(RarcError::NotADirectory { .. })
+    ));
+    assert!(matches!(
+        archive.set_directory_tag("/missing", Some(*b"TEST")),
+        Err(RarcError::NotFound { .. })
+    ));
+}
+
+#[test]
+fn only_custom_tags_are_kept() {
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_directory("/jmp").unwrap();
+    archive.create_directory("/cam").unwrap();
+    archive.set_directory_tag("/cam", Some(*b"ABCD")).unwrap();
+    assert_eq!(archive.custom_directory_tag("/jmp"), None);
+    assert_eq!(archive.custom_directory_tag("/cam"), Some(*b"ABCD"));
+    assert_eq!(archive.custom_directory_tag("/"), None);
+
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+
+    // Stored tags that match the generated ones follow the name of their directory.
+    let mut archive = Archive::from_vec::<BigEndian>(data).unwrap();
+    assert_eq!(archive.custom_directory_tag("/jmp"), None);
+    assert_eq!(archive.custom_directory_tag("/cam"), Some(*b"ABCD"));
+    archive.mv("/jmp", "/map").unwrap();
+    assert_eq!(archive.directory_tag("/map"), Some(*b"MAP "));
+}
+
+#[cfg(feature = "manifest")]
+#[test]
+fn manifest_keeps_custom_tags_and_data_order() {
+    let mut archive = Archive::new("stage").unwrap();
+    archive.create_file("/jmp/List", b"list".to_vec()).unwrap();
+    archive.create_file("/jmp/Base", b"base".to_vec()).unwrap();
+    archive.create_file("/a", vec![1; 40]).unwrap();
+    archive.create_file("/b", vec![2; 20]).unwrap();
+    archive.create_directory("/cam").unwrap();
+    archive.set_directory_tag("/cam", Some(*b"ABCD")).unwrap();
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+
+    // Renaming keeps the data where it is, so it's no longer stored in name order.
+    let mut archive = Archive::from_vec::<BigEndian>(data).unwrap();
+    archive.mv("/a", "/z").unwrap();
+    archive.mv("/jmp/Base", "/jmp/Zone").unwrap();
+    let mut data = Vec::new();
+    archive.write::<BigEndian>(&mut data).unwrap();
+    let mut archive = Archive::from_vec::<BigEndian>(data.clone()).unwrap();
+
+    let path = std::env::temp_dir().join(format!("rarc-tags-{}", std::process::id()));
+    archive.extract_to(&path).unwrap();
+    let manifest = std::fs::read_to_string(path.join(rarc::MANIFEST_NAME)).unwrap();
+    // The files of a packed archive are read when it is written.
+    let mut packed = Archive::from_directory(&path).unwrap();
+    let mut written = Vec::new();
+    let result = packed.write::<BigEndian>(&mut written);
+    std::fs::remove_dir_all(&path).unwrap();
+    result.unwrap();
+
+    assert!(manifest.contains("ABCD"));
+    assert!(!manifest.contains("JMP"));
+    assert_eq!(packed.custom_directory_tag("/jmp"), None);
+    assert_eq!(packed.custom_directory_tag("/cam"), Some(*b"ABCD"));
+    assert_eq!(written, data);
+}
@@ -0,0 +1,153 @@
+use rarc::byteorder::BigEndian;
+use rarc::{Archive, ArchiveView, EntryKind, RarcError};
+
+fn sample() -> Archive<std::io::Empty> {
+    let mut archive = Archive::new("stage").unwrap();
+    archive
+        .create_file("/jmp/Placement/ObjInfo", b"objects".to_vec())
+        .unwrap();
+    archive.create_file("/jmp/List", b"list".to_vec()).unwrap();
+    archive.create_directory("/obj").unwrap();
+    archive.create_file("/readme", b"hello".to_vec()).unwrap();
+    archive
+}
+
+fn write<F: std::io::Read + std::io::Seek>(archive: &mut Archive<F>) -> Vec<u8> {
+    let mut result = Vec::new();
+    archive.write::<BigEndian>(&mut result).unwrap();
+    result
+}
+
+#[test]
+fn move_file_into_another_directory() {
+    let mut archive = sample();
+    archive.mv("/readme", "/obj/readme").unwrap();
+    assert!(!archive.contains("/readme"));
+
+    let mut archive = Archive::from_vec::<BigEndian>(write(&mut archive)).unwrap();
+    assert!(!archive.contains("/readme"));
+    assert_eq!(
+        archive
+            .open_file("/obj/readme")
+            .unwrap()
+            .read_to_vec()
+            .unwrap(),
+        b"hello"
+    );
+
+    // Moving into a directory that doesn't exist yet creates it.
+    archive.mv("/jmp/List", "/new/List").unwrap();
+    assert!(archive.is_directory("/new"));
+    assert!(!archive.contains("/jmp/List"));
+}
+
+#[test]
+fn move_directory_with_its_contents() {
+    let mut archive = sample();
+    archive.mv("/jmp", "/obj/jmp").unwrap();
+    assert_eq!(
+        archive.iter().collect::<Vec<_>>(),
+        [
+            ("/", EntryKind::Directory),
+            ("/obj", EntryKind::Directory),
+            ("/obj/jmp", EntryKind::Directory),
+            ("/obj/jmp/List", EntryKind::File),
+            ("/obj/jmp/Placement", EntryKind::Directory),
+            ("/obj/jmp/Placement/ObjInfo", EntryKind::File),
+            ("/readme", EntryKind::File),
+        ]
+    );
+}
+
+#[test]
+fn move_directory_under_itself() {
+    let mut archive = sample();
+    for target in ["/jmp/Placement/jmp", "/jmp/jmp"] {
+        assert!(matches!(
+            archive.mv("/jmp", target),
+            Err(RarcError::MoveIntoItself { path }) if path == "/jmp"
+        ));
+    }
+    assert!(archive.contains("/jmp/Placement/ObjInfo"));
+    assert!(!archive.contains("/jmp/jmp"));
+
+    assert!(matches!(
+        archive.mv("/jmp", "/readme"),
+        Err(RarcError::AlreadyExists { .. })
+    ));
+    assert!(matches!(
+        archive.mv("/", "/root"),
+        Err(RarcError::RootDirectory)
+    ));
+}
+
+#[test]
+fn remove_non_empty_directory() {
+    let mut archive = sample();
+    assert!(archive.remove("/jmp").unwrap());
+    assert!(!archive.remove("/jmp").unwrap());
+    assert_eq!(
+        archive.iter().collect::<Vec<_>>(),
+        [
+            ("/", EntryKind::Directory),
+            ("/obj", EntryKind::Directory),
+            ("/readme", EntryKind::File),
+        ]
+    );
+    assert!(matches!(archive.remove("/"), Err(RarcError::RootDirectory)));
+
+    let archive = Archive::from_vec::<BigEndian>(write(&mut archive)).unwrap();
+    assert_eq!(archive.files().collect::<Vec<_>>(), ["/readme"]);
+}
+
+#[test]
+fn create_directory() {
+    let mut archive = sample();
+    archive.create_directory("/a/b/c").unwrap();
+    assert!(archive.is_directory("/a"));
+    assert!(archive.is_directory("/a/b"));
+    assert!(archive.is_directory("a/b/c/"));
+
+    // Creating an existing directory does nothing.
+    archive.create_directory("/jmp").unwrap();
+    assert!(archive.contains("/jmp/List"));
+
+    assert!(matches!(
+        archive.create_directory("/readme"),
+        Err(RarcError::NotADirectory { .. })
+    ));
+    assert!(matches!(
+        archive.create_directory("/readme/a"),
+        Err(RarcError::NotADirectory { .. })
+    ));
+    assert!(matches!(
+        archive.create_file("/jmp", Vec::new()),
+        Err(RarcError::IsADirectory { .. })
+    ));
+}
+
+#[test]
+fn iter_follows_node_order() {
+    let data = write(&mut sample());
+    let view = ArchiveView::<BigEndian>::new(&data).unwrap();
+    let nodes = view
+        .file_nodes()
+        .iter()
+        .filter_map(|node| view.name(node))
+        .filter(|name| *name != "." && *name != "..")
+        .collect::<Vec<_>>();
+
+    let archive = Archive::from_vec::<BigEndian>(data.clone()).unwrap();
+    let entries = archive.iter().collect::<Vec<_>>();
+    assert_eq!(entries[0], ("/", EntryKind::Directory));
+
+    let names = entries[1..]
+        .iter()
+        .map(|(path, _)| path.rsplit('/').next().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, nodes);
+    assert_eq!(
+        names,
+        ["jmp", "obj", "readme", "List", "Placement", "ObjInfo"]
+    );
+}
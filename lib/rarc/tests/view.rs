@@ -0,0 +1,185 @@
+use rarc::byteorder::{BigEndian, ByteOrder, LittleEndian};
+use rarc::compression::Format;
+use rarc::{Archive, ArchiveView};
+use std::io::Cursor;
+
+const FILES: [&str; 4] = ["/jmp/Placement/ObjInfo", "/jmp/List", "/readme", "/packed"];
+
+fn sample<T: ByteOrder>() -> Vec<u8> {
+    let mut archive = Archive::new("stage").unwrap();
+    archive
+        .create_file("/jmp/Placement/ObjInfo", b"objects".to_vec())
+        .unwrap();
+    archive.create_file("/jmp/List", vec![0xAB; 100]).unwrap();
+    archive.create_file("/readme", b"hello".to_vec()).unwrap();
+    archive
+        .create_file("/packed", b"packed ".repeat(30))
+        .unwrap();
+    archive.create_directory("/empty").unwrap();
+
+    let mut file = archive.open_file("/packed").unwrap();
+    let mut attributes = file.attributes();
+    attributes.set_compression(Some(Format::Yaz0));
+    file.set_attributes(attributes);
+
+    let mut data = Vec::new();
+    archive.write::<T>(&mut data).unwrap();
+    data
+}
+
+/// Copies the data into a buffer that starts at a 4 byte boundary, or one byte after it.
+fn buffer(data: &[u8], aligned: bool) -> (Vec<u32>, usize) {
+    let start = usize::from(!aligned);
+    let mut result = vec![0u32; (data.len() + start).div_ceil(4)];
+    bytemuck::cast_slice_mut::<u32, u8>(&mut result)[start..(start + data.len())]
+        .copy_from_slice(data);
+    (result, start)
+}
+
+/// Checks that the stored contents of every file match the ones read through an archive.
+fn check_view<T: ByteOrder>(view: &ArchiveView<'_, T>, data: &[u8]) {
+    let mut archive = Archive::from_vec::<T>(data.to_vec()).unwrap();
+    for path in FILES {
+        let mut file = archive.open_file(path).unwrap();
+        assert_eq!(view.get(path).unwrap(), file.read_raw_to_vec().unwrap());
+    }
+    assert_eq!(view.get("/missing"), None);
+    assert_eq!(view.get("/empty"), None);
+    assert_eq!(view.root_name(), Some("stage"));
+
+    let mut paths = view.files().map(|x| x.unwrap().0).collect::<Vec<_>>();
+    paths.sort();
+    let mut expected = FILES.to_vec();
+    expected.sort();
+    assert_eq!(paths, expected);
+}
+
+fn view<T: ByteOrder>(aligned: bool) {
+    let data = sample::<T>();
+    let (buffer, start) = buffer(&data, aligned);
+    let bytes = &bytemuck::cast_slice::<u32, u8>(&buffer)[start..(start + data.len())];
+
+    let view = ArchiveView::<T>::new(bytes).unwrap();
+    // The node tables are only copied if they can't be borrowed from the buffer.
+    let nodes = view.directory_nodes().as_ptr() as *const u8;
+    assert_eq!(bytes.as_ptr_range().contains(&nodes), aligned);
+    let nodes = view.file_nodes().as_ptr() as *const u8;
+    assert_eq!(bytes.as_ptr_range().contains(&nodes), aligned);
+
+    check_view(&view, &data);
+}
+
+#[test]
+fn aligned_view() {
+    view::<BigEndian>(true);
+    view::<LittleEndian>(true);
+}
+
+#[test]
+fn unaligned_view() {
+    view::<BigEndian>(false);
+    view::<LittleEndian>(false);
+}
+
+fn file_slice<T: ByteOrder>(aligned: bool) {
+    let data = sample::<T>();
+    let (buffer, start) = buffer(&data, aligned);
+    let bytes = &bytemuck::cast_slice::<u32, u8>(&buffer)[start..(start + data.len())];
+
+    let mut archive = Archive::read::<T>(Cursor::new(bytes)).unwrap();
+    for path in FILES {
+        let slice = archive.file_slice(path).unwrap().to_vec();
+        let mut file = archive.open_file(path).unwrap();
+        assert_eq!(slice, file.read_raw_to_vec().unwrap());
+        if file.stored_compression().is_none() {
+            assert_eq!(slice, file.read_to_vec().unwrap());
+        }
+    }
+    assert_eq!(archive.file_slice("/empty"), None);
+
+    // Replaced contents are returned from memory.
+    archive
+        .open_file("/readme")
+        .unwrap()
+        .replace(b"replaced".to_vec())
+        .unwrap();
+    assert_eq!(archive.file_slice("/readme"), Some(&b"replaced"[..]));
+}
+
+#[test]
+fn aligned_file_slice() {
+    file_slice::<BigEndian>(true);
+    file_slice::<LittleEndian>(true);
+}
+
+#[test]
+fn unaligned_file_slice() {
+    file_slice::<BigEndian>(false);
+    file_slice::<LittleEndian>(false);
+}
+
+#[test]
+fn compressed_archives_are_not_viewable() {
+    let data = yaz0::compress(&sample::<BigEndian>(), Format::Yaz0, Default::default());
+    assert!(ArchiveView::<BigEndian>::new(&data).is_err());
+
+    // They are decompressed into memory instead.
+    let archive = Archive::from_vec::<BigEndian>(data).unwrap();
+    assert_eq!(archive.file_slice("/readme"), Some(&b"hello"[..]));
+}
+
+/// A file in the temporary directory that gets deleted again.
+struct TempFile(std::path::PathBuf);
+
+impl TempFile {
+    fn new(name: &str, data: &[u8]) -> Self {
+        let path = std::env::temp_dir().join(format!("rarc-{name}-{}.arc", std::process::id()));
+        std::fs::write(&path, data).unwrap();
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn opened_archives_only_slice_from_memory() {
+    let data = sample::<BigEndian>();
+    let file = TempFile::new("open", &data);
+    let archive = Archive::open::<BigEndian>(&file.0).unwrap();
+    assert_eq!(archive.file_slice("/readme"), None);
+
+    let compressed = yaz0::compress(&data, Format::Yay0, Default::default());
+    let file = TempFile::new("open-compressed", &compressed);
+    let archive = Archive::open::<BigEndian>(&file.0).unwrap();
+    assert_eq!(archive.file_slice("/readme"), Some(&b"hello"[..]));
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn mapped_archives() {
+    for (name, data) in [
+        ("map", sample::<BigEndian>()),
+        (
+            "map-compressed",
+            yaz0::compress(&sample::<BigEndian>(), Format::Yaz0, Default::default()),
+        ),
+    ] {
+        let file = TempFile::new(name, &data);
+        let mut archive = unsafe { Archive::map::<BigEndian>(&file.0) }.unwrap();
+        for path in FILES {
+            let slice = archive.file_slice(path).unwrap().to_vec();
+            assert_eq!(
+                slice,
+                archive.open_file(path).unwrap().read_raw_to_vec().unwrap()
+            );
+        }
+        assert_eq!(
+            archive.open_file("/readme").unwrap().read_to_vec().unwrap(),
+            b"hello"
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! # Nintendo Yaz0 / Yay0 Compression
+//!
+//! Yaz0 and Yay0 are two LZ77 based compression formats used by Nintendo in GameCube and Wii games.
+//! Both formats share the same back reference encoding, but Yaz0 interleaves flags, literals and back references into one stream,
+//! while Yay0 splits them up into three seperate streams.
+//! Yaz0 compressed files usually have the `.szs` extension and Yay0 compressed files the `.szp` extension.
+
+mod matcher;
+mod yay0;
+mod yaz0;
+
+use thiserror::Error;
+
+/// One of the two supported compression formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The Yaz0 format with a single interleaved data stream.
+    Yaz0,
+
+    /// The Yay0 format with seperate flag, back reference and literal streams.
+    Yay0,
+}
+
+impl Format {
+    /// Detects the compression format from the magic number at the start of the data.
+    /// Returns [`None`] if the data isn't compressed.
+    pub fn detect(data: &[u8]) -> Option<Format> {
+        match data.get(..4)? {
+            b"Yaz0" => Some(Format::Yaz0),
+            b"Yay0" => Some(Format::Yay0),
+            _ => None,
+        }
+    }
+
+    /// Gets the magic number that compressed data of this format starts with.
+    pub fn magic(self) -> &'static [u8; 4] {
+        match self {
+            Format::Yaz0 => b"Yaz0",
+            Format::Yay0 => b"Yay0",
+        }
+    }
+}
+
+/// How much effort the compressor puts into finding back references.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionLevel {
+    /// Only checks the most recent candidate for each back reference.
+    /// This is by far the fastest level, but the output is noticeably bigger.
+    Fast,
+
+    /// A level between 1 and 10, with 1 being faster but bigger and 10 being smaller but slower.
+    /// Values outside of this range get clamped.
+    Level(u8),
+}
+
+impl Default for CompressionLevel {
+    fn default() -> Self {
+        CompressionLevel::Level(10)
+    }
+}
+
+type Result<T> = std::result::Result<T, Yaz0Error>;
+
+/// Errors that get returned from this library.
+#[derive(Debug, Error)]
+pub enum Yaz0Error {
+    #[error("the data doesn't start with a Yaz0 or Yay0 magic number")]
+    InvalidMagic,
+    #[error("the compressed data ended unexpectedly")]
+    UnexpectedEof,
+    #[error("a back reference at offset {offset} points before the start of the data")]
+    InvalidReference { offset: usize },
+}
+
+/// Compresses the given data using the given format and compression level.
+pub fn compress(data: &[u8], format: Format, level: CompressionLevel) -> Vec<u8> {
+    match format {
+        Format::Yaz0 => yaz0::compress(data, level),
+        Format::Yay0 => yay0::compress(data, level),
+    }
+}
+
+/// Decompresses the given data, detecting whether it is Yaz0 or Yay0 compressed.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    match Format::detect(data) {
+        Some(Format::Yaz0) => yaz0::decompress(data),
+        Some(Format::Yay0) => yay0::decompress(data),
+        None => Err(Yaz0Error::InvalidMagic),
+    }
+}
+
+/// Gets the size of the data after decompression, as stored in the header.
+pub fn decompressed_size(data: &[u8]) -> Result<usize> {
+    Format::detect(data).ok_or(Yaz0Error::InvalidMagic)?;
+    read_u32(data, 4).map(|x| x as usize)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32> {
+    let bytes = data
+        .get(offset..(offset + 4))
+        .ok_or(Yaz0Error::UnexpectedEof)?;
+    Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+fn copy_back_reference(
+    output: &mut Vec<u8>,
+    distance: usize,
+    length: usize,
+    offset: usize,
+) -> Result<()> {
+    if distance > output.len() {
+        return Err(Yaz0Error::InvalidReference { offset });
+    }
+
+    let start = output.len() - distance;
+    if distance >= length {
+        output.extend_from_within(start..(start + length));
+    } else {
+        for i in 0..length {
+            output.push(output[start + i]);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use crate::CompressionLevel;
+
+pub const WINDOW_SIZE: usize = 0x1000;
+pub const MIN_LENGTH: usize = 3;
+pub const MAX_LENGTH: usize = 0xFF + 0x12;
+
+const HASH_BITS: u32 = 15;
+const NONE: u32 = u32::MAX;
+
+pub enum Token {
+    Literal(u8),
+    BackReference { distance: usize, length: usize },
+}
+
+/// Splits the data into literals and back references, using a hash chain to find matches.
+pub fn tokenize(data: &[u8], level: CompressionLevel, mut emit: impl FnMut(Token)) {
+    let (depth, lazy) = match level {
+        CompressionLevel::Fast => (1, false),
+        CompressionLevel::Level(x) => {
+            let x = x.clamp(1, 10);
+            (1 << (x + 2), x >= 5)
+        }
+    };
+
+    let mut matcher = Matcher {
+        data,
+        depth,
+        head: vec![NONE; 1 << HASH_BITS],
+        previous: vec![NONE; WINDOW_SIZE],
+    };
+
+    let mut position = 0;
+    while position < data.len() {
+        let (length, distance) = matcher.find(position);
+
+        if length < MIN_LENGTH {
+            matcher.insert(position);
+            emit(Token::Literal(data[position]));
+            position += 1;
+            continue;
+        }
+
+        matcher.insert(position);
+        if lazy && matcher.find(position + 1).0 > length {
+            emit(Token::Literal(data[position]));
+            position += 1;
+            continue;
+        }
+
+        for i in (position + 1)..(position + length) {
+            matcher.insert(i);
+        }
+
+        emit(Token::BackReference { distance, length });
+        position += length;
+    }
+}
+
+struct Matcher<'a> {
+    data: &'a [u8],
+    depth: usize,
+    head: Vec<u32>,
+    previous: Vec<u32>,
+}
+
+impl<'a> Matcher<'a> {
+    fn hash(&self, position: usize) -> Option<usize> {
+        let bytes = self.data.get(position..(position + MIN_LENGTH))?;
+        let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        Some((value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize)
+    }
+
+    fn insert(&mut self, position: usize) {
+        if let Some(hash) = self.hash(position) {
+            self.previous[position % WINDOW_SIZE] = self.head[hash];
+            self.head[hash] = position as u32;
+        }
+    }
+
+    /// Finds the longest match for the given position, returning its length and distance.
+    fn find(&self, position: usize) -> (usize, usize) {
+        let hash = match self.hash(position) {
+            Some(x) => x,
+            None => return (0, 0),
+        };
+
+        let max_length = MAX_LENGTH.min(self.data.len() - position);
+        let mut best = (0, 0);
+        let mut candidate = self.head[hash];
+
+        for _ in 0..self.depth {
+            if candidate == NONE {
+                break;
+            }
+
+            let start = candidate as usize;
+            let distance = position - start;
+            if distance == 0 || distance > WINDOW_SIZE {
+                break;
+            }
+
+            let length = self.data[start..]
+                .iter()
+                .zip(&self.data[position..(position + max_length)])
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            if length > best.0 {
+                best = (length, distance);
+                if length == max_length {
+                    break;
+                }
+            }
+
+            let next = self.previous[start % WINDOW_SIZE];
+            if next != NONE && next as usize >= start {
+                break;
+            }
+            candidate = next;
+        }
+
+        best
+    }
+}
@@ -0,0 +1,108 @@
+use crate::matcher::{self, Token};
+use crate::{copy_back_reference, read_u32, CompressionLevel, Result, Yaz0Error};
+
+pub fn compress(data: &[u8], level: CompressionLevel) -> Vec<u8> {
+    let mut masks = Vec::with_capacity(data.len() / 64 + 1);
+    let mut links = Vec::with_capacity(data.len() / 4);
+    let mut chunks = Vec::with_capacity(data.len() / 2);
+
+    let mut mask = 0u32;
+    let mut bit = 0;
+
+    matcher::tokenize(data, level, |token| {
+        match token {
+            Token::Literal(value) => {
+                mask |= 0x8000_0000 >> bit;
+                chunks.push(value);
+            }
+            Token::BackReference { distance, length } => {
+                let distance = distance - 1;
+                if length >= 0x12 {
+                    links.extend_from_slice(&(distance as u16).to_be_bytes());
+                    chunks.push((length - 0x12) as u8);
+                } else {
+                    links
+                        .extend_from_slice(&(((length - 2) << 12 | distance) as u16).to_be_bytes());
+                }
+            }
+        }
+
+        bit += 1;
+        if bit == 32 {
+            masks.push(mask);
+            mask = 0;
+            bit = 0;
+        }
+    });
+
+    if bit != 0 {
+        masks.push(mask);
+    }
+
+    let link_offset = 0x10 + masks.len() * 4;
+    let chunk_offset = link_offset + links.len();
+
+    let mut output = Vec::with_capacity(chunk_offset + chunks.len());
+    output.extend_from_slice(b"Yay0");
+    output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    output.extend_from_slice(&(link_offset as u32).to_be_bytes());
+    output.extend_from_slice(&(chunk_offset as u32).to_be_bytes());
+    for mask in masks {
+        output.extend_from_slice(&mask.to_be_bytes());
+    }
+    output.extend_from_slice(&links);
+    output.extend_from_slice(&chunks);
+    output
+}
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let size = read_u32(data, 4)? as usize;
+    let mut link_position = read_u32(data, 8)? as usize;
+    let mut chunk_position = read_u32(data, 12)? as usize;
+    let mut mask_position = 0x10;
+
+    // The size comes from the header, so it is only trusted as far as the input could expand to.
+    let mut output = Vec::with_capacity(size.min(data.len().saturating_mul(9)));
+    let mut mask = 0;
+    let mut bits = 0;
+
+    while output.len() < size {
+        if bits == 0 {
+            mask = read_u32(data, mask_position)?;
+            mask_position += 4;
+            bits = 32;
+        }
+
+        let literal = mask & 0x8000_0000 != 0;
+        mask <<= 1;
+        bits -= 1;
+
+        if literal {
+            let value = *data.get(chunk_position).ok_or(Yaz0Error::UnexpectedEof)?;
+            chunk_position += 1;
+            output.push(value);
+            continue;
+        }
+
+        let link = data
+            .get(link_position..(link_position + 2))
+            .ok_or(Yaz0Error::UnexpectedEof)?;
+        let link = u16::from_be_bytes([link[0], link[1]]) as usize;
+        link_position += 2;
+
+        let distance = (link & 0x0FFF) + 1;
+        let length = match link >> 12 {
+            0 => {
+                let value = *data.get(chunk_position).ok_or(Yaz0Error::UnexpectedEof)?;
+                chunk_position += 1;
+                value as usize + 0x12
+            }
+            x => x + 2,
+        };
+
+        let offset = output.len();
+        copy_back_reference(&mut output, distance, length.min(size - offset), offset)?;
+    }
+
+    Ok(output)
+}
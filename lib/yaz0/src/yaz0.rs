@@ -0,0 +1,84 @@
+use crate::matcher::{self, Token};
+use crate::{copy_back_reference, read_u32, CompressionLevel, Result, Yaz0Error};
+
+pub fn compress(data: &[u8], level: CompressionLevel) -> Vec<u8> {
+    let mut output = Vec::with_capacity(data.len() / 2 + 0x10);
+    output.extend_from_slice(b"Yaz0");
+    output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    output.extend_from_slice(&[0; 8]);
+
+    let mut flags = 0;
+    let mut bit = 8;
+
+    matcher::tokenize(data, level, |token| {
+        if bit == 8 {
+            flags = output.len();
+            output.push(0);
+            bit = 0;
+        }
+
+        match token {
+            Token::Literal(value) => {
+                output[flags] |= 0x80 >> bit;
+                output.push(value);
+            }
+            Token::BackReference { distance, length } => {
+                let distance = distance - 1;
+                if length >= 0x12 {
+                    output.push((distance >> 8) as u8);
+                    output.push(distance as u8);
+                    output.push((length - 0x12) as u8);
+                } else {
+                    output.push(((length - 2) << 4 | distance >> 8) as u8);
+                    output.push(distance as u8);
+                }
+            }
+        }
+
+        bit += 1;
+    });
+
+    output
+}
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let size = read_u32(data, 4)? as usize;
+    // The size comes from the header, so it is only trusted as far as the input could expand to.
+    let mut output = Vec::with_capacity(size.min(data.len().saturating_mul(9)));
+    let mut position = 0x10;
+
+    let mut next = || {
+        let value = *data.get(position).ok_or(Yaz0Error::UnexpectedEof)?;
+        position += 1;
+        Ok(value)
+    };
+
+    while output.len() < size {
+        let flags = next()?;
+
+        for bit in 0..8 {
+            if output.len() >= size {
+                break;
+            }
+
+            if flags & (0x80 >> bit) != 0 {
+                output.push(next()?);
+                continue;
+            }
+
+            let offset = output.len();
+            let high = next()? as usize;
+            let low = next()? as usize;
+
+            let distance = ((high & 0x0F) << 8 | low) + 1;
+            let length = match high >> 4 {
+                0 => next()? as usize + 0x12,
+                x => x + 2,
+            };
+
+            copy_back_reference(&mut output, distance, length.min(size - offset), offset)?;
+        }
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,87 @@
+use yaz0::{CompressionLevel, Format, Yaz0Error};
+
+const FORMATS: [Format; 2] = [Format::Yaz0, Format::Yay0];
+
+fn levels() -> Vec<CompressionLevel> {
+    let mut result = vec![CompressionLevel::Fast];
+    result.extend((0..=11).map(CompressionLevel::Level));
+    result
+}
+
+fn samples() -> Vec<Vec<u8>> {
+    let text = b"The quick brown fox jumps over the lazy dog. ".repeat(40);
+    let noise = (0..5000u32)
+        .map(|x| (x.wrapping_mul(2654435761) >> 13) as u8)
+        .collect();
+    vec![
+        Vec::new(),
+        vec![0x42],
+        b"abc".to_vec(),
+        vec![0; 10000],
+        text,
+        noise,
+        (0..=255).cycle().take(3000).collect(),
+    ]
+}
+
+#[test]
+fn round_trip() {
+    for format in FORMATS {
+        for level in levels() {
+            for sample in samples() {
+                let compressed = yaz0::compress(&sample, format, level);
+                assert_eq!(Format::detect(&compressed), Some(format));
+                assert_eq!(yaz0::decompressed_size(&compressed).unwrap(), sample.len());
+                assert_eq!(
+                    yaz0::decompress(&compressed).unwrap(),
+                    sample,
+                    "{format:?} {level:?} with {} bytes",
+                    sample.len()
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn truncated_input() {
+    for format in FORMATS {
+        for sample in samples() {
+            let compressed = yaz0::compress(&sample, format, CompressionLevel::default());
+            for length in 0..compressed.len() {
+                // Empty data only needs the magic and size, the rest of the header is never read by Yaz0.
+                if sample.is_empty() && length >= 8 && format == Format::Yaz0 {
+                    continue;
+                }
+                let result = yaz0::decompress(&compressed[..length]);
+                assert!(result.is_err(), "{format:?} cut to {length} bytes");
+            }
+        }
+    }
+}
+
+#[test]
+fn huge_declared_size() {
+    for format in FORMATS {
+        let mut data = format.magic().to_vec();
+        data.extend(u32::MAX.to_be_bytes());
+        data.extend(0x10u32.to_be_bytes());
+        data.extend(0x10u32.to_be_bytes());
+        assert!(matches!(
+            yaz0::decompress(&data),
+            Err(Yaz0Error::UnexpectedEof)
+        ));
+    }
+}
+
+#[test]
+fn invalid_magic() {
+    assert!(matches!(
+        yaz0::decompress(b"Yaz1\0\0\0\0"),
+        Err(Yaz0Error::InvalidMagic)
+    ));
+    assert!(matches!(
+        yaz0::decompress(b""),
+        Err(Yaz0Error::InvalidMagic)
+    ));
+}